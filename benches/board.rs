@@ -0,0 +1,189 @@
+//! Timings of the engine on large boards, run with `cargo bench`. Every benchmark times
+//! the engine against the straightforward implementation it replaced.
+
+use std::{
+    collections::HashSet,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use cli_minesweeper::{
+    count_neighbouring_mines, for_each_neighbour, generate_mine_positions,
+    get_neighbouring_indices, Board, Coord, Tile,
+};
+use rand::{rngs::StdRng, seq::IteratorRandom, SeedableRng};
+
+const ROW_COUNT: usize = 100;
+const COL_COUNT: usize = 100;
+const MINE_COUNT: usize = 500;
+const FIRST_CLICK: usize = 50 * COL_COUNT + 50;
+const ITERATIONS: u32 = 20;
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut board = Board::new(ROW_COUNT, COL_COUNT);
+    board.generate_mines(MINE_COUNT, FIRST_CLICK, 1, &mut rng);
+
+    bench_reveal(&board);
+    bench_mine_storage(&board);
+    bench_neighbours(&board);
+}
+
+/// Runs `f` a few times and prints the average time it took.
+fn time(name: &str, mut f: impl FnMut()) -> Duration {
+    f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let average = start.elapsed() / ITERATIONS;
+
+    println!("{:<40} {:>12.3?}", name, average);
+    average
+}
+
+fn compare(name: &str, before: Duration, after: Duration) {
+    println!(
+        "{:<40} {:>11.1}x\n",
+        name,
+        before.as_secs_f64() / after.as_secs_f64()
+    );
+}
+
+/// The flood fill of a 100×100 board with the cached mine counts against counting the
+/// mines of every opened tile.
+fn bench_reveal(board: &Board) {
+    let mut mines = vec![false; ROW_COUNT * COL_COUNT];
+    for idx in board.mines() {
+        mines[idx] = true;
+    }
+
+    let before = time("reveal, counting the mines", || {
+        let mut tiles = board.tiles().to_vec();
+        reveal_counting(&mut tiles, &mines, FIRST_CLICK);
+        black_box(tiles);
+    });
+    let after = time("reveal, cached mine counts", || {
+        let mut board = board.clone();
+        board.reveal(FIRST_CLICK);
+        black_box(board);
+    });
+    compare("reveal speedup", before, after);
+}
+
+/// The flood fill as it was before the mine counts were cached.
+fn reveal_counting(tiles: &mut [Tile], mines: &[bool], tile_idx: usize) {
+    let mut stack = vec![tile_idx];
+
+    while let Some(tile_idx) = stack.pop() {
+        if tiles[tile_idx] != Tile::Concealed {
+            continue;
+        }
+
+        let mine_count = count_neighbouring_mines(tile_idx, mines, ROW_COUNT, COL_COUNT);
+        tiles[tile_idx] = Tile::Open(mine_count);
+
+        if mine_count == 0 {
+            stack.extend(get_neighbouring_indices(tile_idx, ROW_COUNT, COL_COUNT));
+        }
+    }
+}
+
+/// Generating the mines and the flood fill with the mines in a `Vec<bool>` against a
+/// `HashSet` of mine indices.
+fn bench_mine_storage(board: &Board) {
+    let mut rng = StdRng::seed_from_u64(2);
+    let before = time("generate, HashSet", || {
+        black_box(generate_hashed(&mut rng));
+    });
+    let after = time("generate, Vec<bool>", || {
+        black_box(generate_mine_positions(
+            MINE_COUNT,
+            FIRST_CLICK,
+            ROW_COUNT,
+            COL_COUNT,
+            &mut rng,
+        ));
+    });
+    compare("generate speedup", before, after);
+
+    let hashed_mines = board.mines().collect::<HashSet<_>>();
+    let mut mines = vec![false; ROW_COUNT * COL_COUNT];
+    for idx in board.mines() {
+        mines[idx] = true;
+    }
+
+    let before = time("reveal, HashSet", || {
+        let mut tiles = board.tiles().to_vec();
+        reveal_hashed(&mut tiles, &hashed_mines, FIRST_CLICK);
+        black_box(tiles);
+    });
+    let after = time("reveal, Vec<bool>", || {
+        let mut tiles = board.tiles().to_vec();
+        reveal_counting(&mut tiles, &mines, FIRST_CLICK);
+        black_box(tiles);
+    });
+    compare("reveal speedup", before, after);
+}
+
+/// The mine generation as it was before the mines were stored in a `Vec<bool>`.
+fn generate_hashed(rng: &mut StdRng) -> HashSet<usize> {
+    let mut indices_to_avoid = get_neighbouring_indices(FIRST_CLICK, ROW_COUNT, COL_COUNT);
+    indices_to_avoid.insert(FIRST_CLICK);
+
+    (0..ROW_COUNT * COL_COUNT)
+        .filter(|idx| !indices_to_avoid.contains(idx))
+        .choose_multiple(rng, MINE_COUNT)
+        .into_iter()
+        .collect()
+}
+
+/// Like [`reveal_counting`], but looks the mines up in a `HashSet`.
+fn reveal_hashed(tiles: &mut [Tile], mines: &HashSet<usize>, tile_idx: usize) {
+    let mut stack = vec![tile_idx];
+
+    while let Some(tile_idx) = stack.pop() {
+        if tiles[tile_idx] != Tile::Concealed {
+            continue;
+        }
+
+        let neighbours = get_neighbouring_indices(tile_idx, ROW_COUNT, COL_COUNT);
+        let mine_count = neighbours.iter().filter(|idx| mines.contains(idx)).count();
+        tiles[tile_idx] = Tile::Open(mine_count);
+
+        if mine_count == 0 {
+            stack.extend(neighbours);
+        }
+    }
+}
+
+/// Counting the neighbouring mines of every tile with the neighbours collected into a
+/// `HashSet` against visiting them with [`for_each_neighbour`].
+fn bench_neighbours(board: &Board) {
+    let mut mines = vec![false; ROW_COUNT * COL_COUNT];
+    for idx in board.mines() {
+        mines[idx] = true;
+    }
+
+    let before = time("neighbours, HashSet", || {
+        let mut total = 0;
+        for idx in 0..ROW_COUNT * COL_COUNT {
+            total += get_neighbouring_indices(idx, ROW_COUNT, COL_COUNT)
+                .into_iter()
+                .filter(|&neighbour| mines[neighbour])
+                .count();
+        }
+        black_box(total);
+    });
+    let after = time("neighbours, for_each_neighbour", || {
+        let mut total = 0;
+        for idx in 0..ROW_COUNT * COL_COUNT {
+            let Coord { row, col } = Coord::from_index(idx, COL_COUNT);
+            for_each_neighbour(row, col, ROW_COUNT, COL_COUNT, |neighbour| {
+                total += mines[neighbour] as usize;
+            });
+        }
+        black_box(total);
+    });
+    compare("neighbours speedup", before, after);
+}
@@ -0,0 +1,278 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+
+use cli_minesweeper::{Connectivity, Coord, Error, GameState, GameStatus};
+use serde::{Deserialize, Serialize};
+
+use crate::replay::{Move, Recording};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A finished game for analysis tools, written by `--export-json` and read by
+/// `--replay-json`. Unlike a save file its fields are kept stable, a change increases
+/// `version`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Analysis {
+    pub version: u32,
+    pub rows: usize,
+    pub cols: usize,
+    pub mines: usize,
+    pub wrap: bool,
+    /// The number of neighbours of a tile, 4 or 8.
+    pub connectivity: usize,
+    pub seed: u64,
+    pub question_marks: bool,
+    pub strict_flags: bool,
+    pub lives: usize,
+    pub reveal_budget: Option<usize>,
+    pub status: Status,
+    pub elapsed_seconds: u64,
+    pub clicks: usize,
+    pub hints_used: usize,
+    pub assists_used: usize,
+    pub mine_positions: Vec<[usize; 2]>,
+    /// One string per row in the puzzle format of [`cli_minesweeper::Board`].
+    pub tiles: Vec<String>,
+    pub moves: Vec<MoveRecord>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Playing,
+    Won,
+    Lost,
+}
+
+impl From<Status> for GameStatus {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Playing => GameStatus::Playing,
+            Status::Won => GameStatus::Won,
+            Status::Lost => GameStatus::Lost,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MoveRecord {
+    /// Seconds since the start of the game.
+    pub t: f64,
+    pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub row: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub col: Option<usize>,
+}
+
+impl Analysis {
+    pub fn new(state: &GameState, recording: &Recording) -> Self {
+        let board = &state.board;
+        let settings = &recording.settings;
+
+        Analysis {
+            version: SCHEMA_VERSION,
+            rows: board.row_count(),
+            cols: board.col_count(),
+            mines: state.mine_count,
+            wrap: board.wraps(),
+            connectivity: board.connectivity().neighbour_count(),
+            seed: settings.seed,
+            question_marks: settings.question_marks,
+            strict_flags: settings.strict_flags,
+            lives: settings.lives,
+            reveal_budget: settings.reveal_budget,
+            status: match state.status {
+                GameStatus::Playing => Status::Playing,
+                GameStatus::Won => Status::Won,
+                GameStatus::Lost => Status::Lost,
+            },
+            elapsed_seconds: state.elapsed_seconds(),
+            clicks: state.clicks,
+            hints_used: state.hints_used,
+            assists_used: state.assists_used,
+            mine_positions: board
+                .mines()
+                .map(|idx| {
+                    let coord = Coord::from_index(idx, board.col_count());
+                    [coord.row, coord.col]
+                })
+                .collect(),
+            tiles: board.to_string().lines().map(str::to_string).collect(),
+            moves: recording
+                .moves
+                .iter()
+                .map(|&(t, recorded_move)| MoveRecord::new(t, recorded_move))
+                .collect(),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let analysis: Analysis = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+        if analysis.version != SCHEMA_VERSION {
+            return Err(Error::Parse(format!(
+                "Unsupported analysis version {}, expected {}",
+                analysis.version, SCHEMA_VERSION
+            )));
+        }
+
+        let out_of_range = analysis
+            .mine_positions
+            .iter()
+            .any(|&[row, col]| row >= analysis.rows || col >= analysis.cols);
+        if out_of_range || analysis.mine_positions.len() != analysis.mines {
+            return Err(Error::Parse(format!(
+                "The mine positions do not fit the {}x{} board with {} mines",
+                analysis.rows, analysis.cols, analysis.mines
+            )));
+        }
+
+        Ok(analysis)
+    }
+
+    pub fn connectivity(&self) -> Result<Connectivity, Error> {
+        Connectivity::from_neighbour_count(self.connectivity)
+            .ok_or_else(|| Error::Parse(format!("Invalid connectivity {}", self.connectivity)))
+    }
+
+    /// The mine positions as tile indices.
+    pub fn mine_indices(&self) -> Vec<usize> {
+        self.mine_positions
+            .iter()
+            .map(|&[row, col]| Coord::new(row, col).to_index(self.cols))
+            .collect()
+    }
+
+    /// The recorded moves, failing on an unknown action or a tile outside the board.
+    pub fn moves(&self) -> Result<Vec<Move>, Error> {
+        self.moves
+            .iter()
+            .map(|record| record.to_move(self.rows, self.cols))
+            .collect()
+    }
+}
+
+impl MoveRecord {
+    fn new(t: f64, recorded_move: Move) -> Self {
+        let (action, coord) = match recorded_move {
+            Move::Open(coord) => ("open", Some(coord)),
+            Move::Flag(coord) => ("flag", Some(coord)),
+            Move::Chord(coord) => ("chord", Some(coord)),
+            Move::Undo => ("undo", None),
+            Move::Autoflag => ("autoflag", None),
+            Move::Assist => ("assist", None),
+            Move::Continue => ("continue", None),
+        };
+
+        MoveRecord {
+            t,
+            action: action.to_string(),
+            row: coord.map(|coord| coord.row),
+            col: coord.map(|coord| coord.col),
+        }
+    }
+
+    fn to_move(&self, row_count: usize, col_count: usize) -> Result<Move, Error> {
+        let invalid = || Error::Parse(format!("Invalid move '{}' at t={}", self.action, self.t));
+
+        let coord = || match (self.row, self.col) {
+            (Some(row), Some(col)) if row < row_count && col < col_count => {
+                Ok(Coord::new(row, col))
+            }
+            _ => Err(invalid()),
+        };
+
+        match self.action.as_str() {
+            "open" => Ok(Move::Open(coord()?)),
+            "flag" => Ok(Move::Flag(coord()?)),
+            "chord" => Ok(Move::Chord(coord()?)),
+            "undo" => Ok(Move::Undo),
+            "autoflag" => Ok(Move::Autoflag),
+            "assist" => Ok(Move::Assist),
+            "continue" => Ok(Move::Continue),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        config::Config, replay::Recorder, replay::ReplaySettings, temp_file::TempFile, InputAction,
+    };
+
+    #[test]
+    fn exported_game_round_trips() {
+        let config = Config {
+            row_count: 9,
+            col_count: 9,
+            mine_count: 10,
+            ..Config::default()
+        };
+        let mut state = GameState::new(config.row_count, config.col_count, config.mine_count);
+        let mut rng = StdRng::seed_from_u64(91);
+        let mut recorder = Recorder::new(ReplaySettings::new(&state, &config, 91));
+
+        for action in [
+            InputAction::Open(40),
+            InputAction::Flag(0),
+            InputAction::Flag(1),
+            InputAction::Undo,
+            InputAction::Open(80),
+            InputAction::Open(8),
+        ] {
+            recorder.record(&action);
+            match action {
+                InputAction::Open(idx) => {
+                    crate::open_tile(&mut state, idx, &config, &mut rng, |_| {});
+                }
+                InputAction::Flag(idx) => {
+                    crate::flag_tile(&mut state, idx, &config);
+                }
+                InputAction::Undo => {
+                    state.undo();
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        let exported = Analysis::new(&state, recorder.recording());
+        let file = TempFile::new("json");
+        exported.save(file.path()).unwrap();
+        let imported = Analysis::load(file.path()).unwrap();
+
+        assert_eq!(
+            (imported.rows, imported.cols, imported.mines, imported.seed),
+            (9, 9, 10, 91)
+        );
+        assert_eq!(imported.status, exported.status);
+        assert_eq!(imported.clicks, state.clicks);
+        assert_eq!(imported.mine_positions, exported.mine_positions);
+        assert_eq!(imported.tiles, exported.tiles);
+        assert_eq!(
+            imported.moves().unwrap(),
+            recorder
+                .recording()
+                .moves
+                .iter()
+                .map(|&(_, recorded_move)| recorded_move)
+                .collect::<Vec<_>>()
+        );
+
+        let (replayed, move_count) = crate::replay_analysis(&imported, &config).unwrap();
+        assert_eq!(move_count, 6);
+        assert_eq!(replayed.board, state.board);
+        assert_eq!(replayed.status, state.status);
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    fs,
+    path::PathBuf,
+    process,
+    sync::{Arc, Mutex},
+};
+
+use cli_minesweeper::{Error, GameState, GameStatus};
+
+use crate::messages::{fill, Messages};
+
+/// Keeps a copy of the running game for the Ctrl-C handler, which saves it to
+/// [`path`] before exiting so it can be continued with `--load`.
+pub struct Autosave {
+    state: Arc<Mutex<Option<GameState>>>,
+}
+
+impl Autosave {
+    /// Installs the Ctrl-C handler, which reports the save in the language of `messages`.
+    /// Only one handler can be installed per process.
+    pub fn install(messages: &'static Messages) -> Result<Self, Error> {
+        let state = Arc::new(Mutex::new(None::<GameState>));
+        let handler_state = Arc::clone(&state);
+
+        ctrlc::set_handler(move || {
+            let state = handler_state.lock().ok().and_then(|mut state| state.take());
+            if let Some(state) = state {
+                save(&state, messages);
+            }
+
+            // The conventional exit code of a process ended by SIGINT.
+            process::exit(130);
+        })
+        .map_err(|error| {
+            Error::Config(format!("Failed to install the Ctrl-C handler: {}", error))
+        })?;
+
+        Ok(Autosave { state })
+    }
+
+    /// Remembers the game for the handler. A finished game is not worth saving.
+    pub fn update(&self, state: &GameState) {
+        let snapshot = (state.status == GameStatus::Playing && state.board.has_mines())
+            .then(|| state.snapshot());
+
+        if let Ok(mut state) = self.state.lock() {
+            *state = snapshot;
+        }
+    }
+}
+
+/// Where an interrupted game is saved: `cli-minesweeper/autosave.json` in the user's
+/// config directory.
+pub fn path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_default()
+        .join("cli-minesweeper")
+        .join("autosave.json")
+}
+
+fn save(state: &GameState, messages: &Messages) {
+    let path = path();
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+
+    let path = path.to_string_lossy();
+    match state.save(&path) {
+        Ok(()) => eprintln!("\n{}", fill(messages.autosaved, &[&path])),
+        Err(error) => eprintln!("\n{}", fill(messages.save_failed, &[&path, &error])),
+    }
+}
@@ -0,0 +1,1243 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+    str::FromStr,
+};
+
+use rand::{seq::IteratorRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Tile {
+    Flagged,
+    Question,
+    Concealed,
+    Open(usize),
+    Mine,
+    TriggeredMine,
+    WrongFlag,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OpenResult {
+    Revealed,
+    HitMine,
+}
+
+/// The visible tiles of a board, see [`Board::snapshot`]. The mines are not part of it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoardSnapshot {
+    tiles: Vec<Tile>,
+}
+
+/// The neighbours of a tile grouped by what the player sees, see
+/// [`Board::neighbour_summary`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NeighbourSummary {
+    pub flagged: usize,
+    /// Concealed tiles including the ones marked with a question mark.
+    pub concealed: usize,
+    pub open: usize,
+}
+
+/// Which tiles around a tile count as its neighbours, for the mine counts, chords and
+/// the flood fill alike.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Connectivity {
+    /// Only the tiles above, below, left and right of a tile.
+    Four,
+    /// The orthogonal and the diagonal tiles, as in classic minesweeper.
+    #[default]
+    Eight,
+}
+
+impl Connectivity {
+    /// The connectivity in which a tile has up to `count` neighbours, 4 or 8.
+    pub fn from_neighbour_count(count: usize) -> Option<Self> {
+        match count {
+            4 => Some(Connectivity::Four),
+            8 => Some(Connectivity::Eight),
+            _ => None,
+        }
+    }
+
+    pub fn neighbour_count(self) -> usize {
+        match self {
+            Connectivity::Four => 4,
+            Connectivity::Eight => 8,
+        }
+    }
+}
+
+/// A position on the board. The board itself stores its tiles in a flat row-major
+/// list, so coordinates are converted to indices where they enter the engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Coord {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Coord {
+    pub fn new(row: usize, col: usize) -> Self {
+        Coord { row, col }
+    }
+
+    pub fn from_index(idx: usize, col_count: usize) -> Self {
+        Coord {
+            row: idx / col_count,
+            col: idx % col_count,
+        }
+    }
+
+    pub fn to_index(self, col_count: usize) -> usize {
+        self.row * col_count + self.col
+    }
+}
+
+/// The minesweeper engine: the visible tiles of the board and the hidden mine layout.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Board {
+    tiles: Vec<Tile>,
+    /// `None` until the mines are placed around the first opened tile.
+    mines: Option<Vec<bool>>,
+    row_count: usize,
+    col_count: usize,
+    #[serde(default)]
+    wrap: bool,
+    #[serde(default)]
+    connectivity: Connectivity,
+    /// The number of neighbouring mines for every tile, computed once the mines are placed.
+    #[serde(skip)]
+    mine_counts: Vec<u8>,
+}
+
+impl Board {
+    /// Creates a fully concealed board without any mines. Mines are placed by
+    /// [`Board::generate_mines`] once the first tile is opened.
+    pub fn new(row_count: usize, col_count: usize) -> Self {
+        Board {
+            tiles: vec![Tile::Concealed; row_count * col_count],
+            mines: None,
+            row_count,
+            col_count,
+            wrap: false,
+            connectivity: Connectivity::Eight,
+            mine_counts: vec![0; row_count * col_count],
+        }
+    }
+
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// Iterates over the tiles in row-major order together with their row and column.
+    ///
+    /// ```
+    /// use cli_minesweeper::{coord_to_index, Board};
+    ///
+    /// let board = Board::new(2, 3);
+    /// for (row, col, tile) in board.iter_coords() {
+    ///     assert_eq!(tile, &board.tiles()[coord_to_index(row, col, board.col_count())]);
+    /// }
+    /// ```
+    pub fn iter_coords(&self) -> impl Iterator<Item = (usize, usize, &Tile)> {
+        self.tiles.iter().enumerate().map(|(idx, tile)| {
+            let coord = Coord::from_index(idx, self.col_count);
+            (coord.row, coord.col, tile)
+        })
+    }
+
+    /// Iterates over the indices of all mines.
+    pub fn mines(&self) -> impl Iterator<Item = usize> + '_ {
+        self.mines
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(idx, &is_mine)| is_mine.then_some(idx))
+    }
+
+    /// Whether the mines have been placed yet, which happens on the first opened tile.
+    pub fn has_mines(&self) -> bool {
+        self.mines.is_some()
+    }
+
+    pub fn is_mine(&self, tile_idx: usize) -> bool {
+        self.mines.as_ref().is_some_and(|mines| mines[tile_idx])
+    }
+
+    pub fn mine_count(&self) -> usize {
+        self.mines().count()
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    pub fn col_count(&self) -> usize {
+        self.col_count
+    }
+
+    pub fn wraps(&self) -> bool {
+        self.wrap
+    }
+
+    /// Makes the edges of the board neighbour each other: the left edge touches the
+    /// right edge and the top edge touches the bottom edge.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+        self.update_mine_counts();
+    }
+
+    pub fn connectivity(&self) -> Connectivity {
+        self.connectivity
+    }
+
+    /// Chooses which tiles count as neighbours.
+    ///
+    /// ```
+    /// use cli_minesweeper::{coord_to_index, Board, Connectivity};
+    ///
+    /// let mut board = Board::new(3, 3);
+    /// board.set_connectivity(Connectivity::Four);
+    /// assert_eq!(board.neighbours(coord_to_index(1, 1, 3)).len(), 4);
+    /// assert_eq!(board.neighbours(coord_to_index(0, 0, 3)).len(), 2);
+    /// ```
+    pub fn set_connectivity(&mut self, connectivity: Connectivity) {
+        self.connectivity = connectivity;
+        self.update_mine_counts();
+    }
+
+    pub fn neighbours(&self, tile_idx: usize) -> HashSet<usize> {
+        let mut neighbours = HashSet::new();
+        self.for_each_neighbour(tile_idx, |idx| {
+            neighbours.insert(idx);
+        });
+
+        neighbours
+    }
+
+    /// Calls `f` with the index of every neighbour of a tile, honouring the wrap mode and
+    /// the connectivity of the board. Unlike [`Board::neighbours`] this does not allocate.
+    pub fn for_each_neighbour(&self, tile_idx: usize, mut f: impl FnMut(usize)) {
+        let Coord { row, col } = Coord::from_index(tile_idx, self.col_count);
+
+        // Diagonal neighbours differ from the tile in both the row and the column.
+        let mut visit = |idx| {
+            let neighbour = Coord::from_index(idx, self.col_count);
+            if self.connectivity == Connectivity::Eight
+                || neighbour.row == row
+                || neighbour.col == col
+            {
+                f(idx);
+            }
+        };
+
+        if self.wrap {
+            for_each_wrapping_neighbour(row, col, self.row_count, self.col_count, &mut visit);
+        } else {
+            for_each_neighbour(row, col, self.row_count, self.col_count, &mut visit);
+        }
+    }
+
+    /// Places the mines on the given tiles, e.g. to rebuild a recorded board.
+    pub fn set_mines(&mut self, mine_positions: &[usize]) {
+        let mut mines = vec![false; self.tiles.len()];
+        for &idx in mine_positions {
+            mines[idx] = true;
+        }
+
+        self.mines = Some(mines);
+        self.update_mine_counts();
+    }
+
+    /// Places the mines outside of the tiles within `safe_radius` of the first click. A
+    /// radius of 0 allows a mine under the first click itself.
+    pub fn generate_mines(
+        &mut self,
+        mine_count: usize,
+        first_click: usize,
+        safe_radius: usize,
+        rng: &mut impl Rng,
+    ) {
+        self.mines = Some(choose_mine_positions(
+            mine_count,
+            first_click,
+            self.tiles_within(first_click, safe_radius),
+            self.row_count * self.col_count,
+            rng,
+        ));
+        self.update_mine_counts();
+
+        log::debug!(
+            "Placed {} mines with a safe radius of {} around tile {}",
+            mine_count,
+            safe_radius,
+            first_click
+        );
+    }
+
+    /// All tiles within the given Chebyshev distance of a tile, including the tile itself.
+    /// A radius of 0 gives no tiles at all, which leaves even the tile itself to the mines
+    /// in [`Board::generate_mines`].
+    pub fn tiles_within(&self, tile_idx: usize, radius: usize) -> HashSet<usize> {
+        if radius == 0 {
+            return HashSet::new();
+        }
+
+        let coord = Coord::from_index(tile_idx, self.col_count);
+        let (row, col) = (coord.row as i64, coord.col as i64);
+        let radius = radius.min(self.row_count.max(self.col_count)) as i64;
+        let (row_count, col_count) = (self.row_count as i64, self.col_count as i64);
+
+        let mut tiles = HashSet::new();
+        for neighbour_row in row - radius..=row + radius {
+            for neighbour_col in col - radius..=col + radius {
+                let (neighbour_row, neighbour_col) = if self.wrap {
+                    (
+                        neighbour_row.rem_euclid(row_count),
+                        neighbour_col.rem_euclid(col_count),
+                    )
+                } else if (0..row_count).contains(&neighbour_row)
+                    && (0..col_count).contains(&neighbour_col)
+                {
+                    (neighbour_row, neighbour_col)
+                } else {
+                    continue;
+                };
+
+                tiles.insert(
+                    Coord::new(neighbour_row as usize, neighbour_col as usize)
+                        .to_index(self.col_count),
+                );
+            }
+        }
+
+        tiles
+    }
+
+    /// Recomputes the cached neighbouring mine counts. Needs to be called whenever the
+    /// mine layout changes, including after deserializing a board.
+    pub(crate) fn update_mine_counts(&mut self) {
+        self.mine_counts = (0..self.tiles.len())
+            .map(|idx| self.count_neighbouring_mines(idx) as u8)
+            .collect();
+    }
+
+    /// Counts the mines around a tile, honouring the wrap mode and the connectivity of the
+    /// board. The tile itself is not counted.
+    pub fn count_neighbouring_mines(&self, tile_idx: usize) -> usize {
+        let mut mine_count = 0;
+        self.for_each_neighbour(tile_idx, |idx| {
+            if self.is_mine(idx) {
+                mine_count += 1;
+            }
+        });
+
+        mine_count
+    }
+
+    /// Opens a single tile. Hitting a mine reveals the whole mine layout. Flagged tiles
+    /// are left untouched.
+    pub fn open(&mut self, tile_idx: usize) -> OpenResult {
+        self.open_in_waves(tile_idx, |_| {})
+    }
+
+    /// Like [`Board::open`], but calls `on_wave` after every wave of the flood fill, see
+    /// [`Board::reveal_in_waves`].
+    pub fn open_in_waves(&mut self, tile_idx: usize, on_wave: impl FnMut(&Board)) -> OpenResult {
+        if self.tiles[tile_idx] == Tile::Flagged {
+            return OpenResult::Revealed;
+        }
+
+        if self.is_mine(tile_idx) {
+            self.reveal_all_mines(&[tile_idx]);
+            return OpenResult::HitMine;
+        }
+
+        self.reveal_in_waves(tile_idx, on_wave);
+        OpenResult::Revealed
+    }
+
+    /// Opens all concealed neighbours of a number whose neighbouring flags match its
+    /// count. Does nothing for any other tile.
+    pub fn chord(&mut self, tile_idx: usize) -> OpenResult {
+        let tiles_to_open = self.chord_indices(tile_idx);
+
+        let triggered_mines = tiles_to_open
+            .iter()
+            .copied()
+            .filter(|&idx| self.is_mine(idx))
+            .collect::<Vec<_>>();
+
+        if !triggered_mines.is_empty() {
+            self.reveal_all_mines(&triggered_mines);
+            return OpenResult::HitMine;
+        }
+
+        for idx in tiles_to_open {
+            self.reveal(idx);
+        }
+
+        OpenResult::Revealed
+    }
+
+    /// Flood fills from the given tile, opening every connected tile without
+    /// neighbouring mines and its border of numbers.
+    pub fn reveal(&mut self, tile_idx: usize) {
+        self.reveal_in_waves(tile_idx, |_| {});
+    }
+
+    /// Like [`Board::reveal`], but opens the flood fill one wave at a time: every wave
+    /// opens the concealed neighbours of the zeros opened by the previous one. `on_wave`
+    /// is called with the board after every wave that opened a tile.
+    pub fn reveal_in_waves(&mut self, tile_idx: usize, on_wave: impl FnMut(&Board)) {
+        self.flood_fill(&mut VecDeque::from([tile_idx]), usize::MAX, on_wave);
+    }
+
+    /// Continues a flood fill from the tiles in `frontier`, opening at most `budget`
+    /// tiles. The tiles left to visit stay in `frontier`, so a later call picks up where
+    /// this one stopped. Returns the number of opened tiles.
+    pub fn reveal_limited(&mut self, frontier: &mut VecDeque<usize>, budget: usize) -> usize {
+        self.flood_fill(frontier, budget, |_| {})
+    }
+
+    /// Opens the tiles in `frontier` and, for every opened zero, its concealed
+    /// neighbours, one wave at a time until nothing is left or `budget` tiles are open.
+    /// The tiles of a wave are the ones queued when it starts.
+    fn flood_fill(
+        &mut self,
+        frontier: &mut VecDeque<usize>,
+        budget: usize,
+        mut on_wave: impl FnMut(&Board),
+    ) -> usize {
+        let mut opened = 0;
+
+        while !frontier.is_empty() && opened < budget {
+            let opened_before = opened;
+
+            for _ in 0..frontier.len() {
+                if opened == budget {
+                    break;
+                }
+                let Some(tile_idx) = frontier.pop_front() else {
+                    break;
+                };
+
+                if !matches!(self.tiles[tile_idx], Tile::Concealed | Tile::Question) {
+                    continue;
+                }
+
+                debug_assert!(
+                    !self.is_mine(tile_idx),
+                    "flood fill reached the mine at tile {}",
+                    tile_idx
+                );
+
+                let mine_count = self.mine_counts[tile_idx] as usize;
+                self.tiles[tile_idx] = Tile::Open(mine_count);
+                opened += 1;
+
+                if mine_count == 0 {
+                    self.for_each_neighbour(tile_idx, |idx| {
+                        if matches!(self.tiles[idx], Tile::Concealed | Tile::Question) {
+                            frontier.push_back(idx);
+                        }
+                    });
+                }
+            }
+
+            if opened > opened_before {
+                on_wave(self);
+            }
+        }
+
+        opened
+    }
+
+    /// Counts the flagged, concealed and open neighbours of a tile. The revealed mines of
+    /// a finished game count in neither group, its wrong flags count as flagged.
+    pub fn neighbour_summary(&self, tile_idx: usize) -> NeighbourSummary {
+        let mut summary = NeighbourSummary::default();
+
+        self.for_each_neighbour(tile_idx, |idx| match self.tiles[idx] {
+            Tile::Flagged | Tile::WrongFlag => summary.flagged += 1,
+            Tile::Concealed | Tile::Question => summary.concealed += 1,
+            Tile::Open(_) => summary.open += 1,
+            Tile::Mine | Tile::TriggeredMine => {}
+        });
+
+        summary
+    }
+
+    /// The tiles a chord on the tile opens: its concealed neighbours, if it is an open
+    /// number with as many flagged neighbours. Otherwise there are none.
+    pub fn chord_indices(&self, tile_idx: usize) -> Vec<usize> {
+        let Tile::Open(mine_count) = self.tiles[tile_idx] else {
+            return Vec::new();
+        };
+
+        if self.neighbour_summary(tile_idx).flagged != mine_count {
+            return Vec::new();
+        }
+
+        self.neighbours(tile_idx)
+            .into_iter()
+            .filter(|&idx| matches!(self.tiles[idx], Tile::Concealed | Tile::Question))
+            .collect()
+    }
+
+    /// The concealed tiles next to at least one open number, in ascending order: the
+    /// tiles the numbers say something about. Tiles with a question mark count as
+    /// concealed, flagged tiles are left out like the open ones, since a flag is taken
+    /// as a decided mine.
+    ///
+    /// ```
+    /// use cli_minesweeper::Board;
+    ///
+    /// let board: Board = "01F##\n01?##\n01*##".parse().unwrap();
+    /// assert_eq!(board.frontier(), vec![7, 12]);
+    /// ```
+    pub fn frontier(&self) -> Vec<usize> {
+        (0..self.tiles.len())
+            .filter(|&idx| matches!(self.tiles[idx], Tile::Concealed | Tile::Question))
+            .filter(|&idx| {
+                let mut next_to_number = false;
+                self.for_each_neighbour(idx, |neighbour| {
+                    next_to_number |= matches!(self.tiles[neighbour], Tile::Open(1..));
+                });
+                next_to_number
+            })
+            .collect()
+    }
+
+    /// Cycles a tile through flagged, question mark (if enabled) and concealed.
+    pub fn flag(&mut self, tile_idx: usize, question_marks: bool) {
+        self.tiles[tile_idx] = match self.tiles[tile_idx] {
+            Tile::Flagged if question_marks => Tile::Question,
+            Tile::Flagged => Tile::Concealed,
+            Tile::Question => Tile::Concealed,
+            Tile::Concealed => Tile::Flagged,
+            ref tile => tile.clone(),
+        }
+    }
+
+    pub fn snapshot(&self) -> BoardSnapshot {
+        BoardSnapshot {
+            tiles: self.tiles.clone(),
+        }
+    }
+
+    /// Panics if the snapshot was taken from a board of another size.
+    pub fn restore(&mut self, snapshot: &BoardSnapshot) {
+        assert_eq!(self.tiles.len(), snapshot.tiles.len());
+        self.tiles.clone_from(&snapshot.tiles);
+    }
+
+    /// The changed tiles with their state in the snapshot.
+    pub fn changes_since(&self, snapshot: &BoardSnapshot) -> Vec<(usize, Tile)> {
+        snapshot
+            .tiles
+            .iter()
+            .enumerate()
+            .filter(|&(idx, tile)| self.tiles.get(idx) != Some(tile))
+            .map(|(idx, tile)| (idx, tile.clone()))
+            .collect()
+    }
+
+    /// Puts a tile back into an earlier state, e.g. to undo a move.
+    pub(crate) fn restore_tile(&mut self, tile_idx: usize, tile: Tile) {
+        self.tiles[tile_idx] = tile;
+    }
+
+    /// Flags a concealed or question marked tile, leaving every other tile as it is.
+    pub fn place_flag(&mut self, tile_idx: usize) {
+        if matches!(self.tiles[tile_idx], Tile::Concealed | Tile::Question) {
+            self.tiles[tile_idx] = Tile::Flagged;
+        }
+    }
+
+    /// Finds a concealed tile that is safe to open, deduced from an open number whose
+    /// neighbouring flags already account for all of its mines.
+    pub fn find_safe_tile(&self) -> Option<usize> {
+        (0..self.tiles.len()).find_map(|idx| self.chord_indices(idx).into_iter().min())
+    }
+
+    /// The game is won once every tile that is not a mine has been opened.
+    pub fn is_won(&self) -> bool {
+        let unopened_count = self
+            .tiles
+            .iter()
+            .filter(|tile| !matches!(tile, Tile::Open(_)))
+            .count();
+
+        unopened_count == self.mine_count()
+    }
+
+    /// The Bechtel's Board Benchmark Value: the minimum number of clicks needed to open
+    /// every safe tile. Each connected region of zeros counts as one click, as does every
+    /// number that is not on the border of such a region.
+    pub fn three_bv(&self) -> usize {
+        let mine_counts = &self.mine_counts;
+        let mut visited = vec![false; self.tiles.len()];
+        let mut clicks = 0;
+
+        for start in 0..self.tiles.len() {
+            if visited[start] || mine_counts[start] != 0 || self.is_mine(start) {
+                continue;
+            }
+
+            clicks += 1;
+            visited[start] = true;
+            let mut region = VecDeque::from([start]);
+
+            while let Some(idx) = region.pop_front() {
+                if mine_counts[idx] != 0 {
+                    continue;
+                }
+
+                for neighbour in self.neighbours(idx) {
+                    if !visited[neighbour] && !self.is_mine(neighbour) {
+                        visited[neighbour] = true;
+                        region.push_back(neighbour);
+                    }
+                }
+            }
+        }
+
+        clicks
+            + (0..self.tiles.len())
+                .filter(|&idx| !visited[idx] && !self.is_mine(idx))
+                .count()
+    }
+
+    pub fn count_open(&self) -> usize {
+        self.tiles
+            .iter()
+            .filter(|tile| matches!(tile, Tile::Open(_)))
+            .count()
+    }
+
+    pub fn count_flags(&self) -> usize {
+        self.tiles
+            .iter()
+            .filter(|&tile| *tile == Tile::Flagged)
+            .count()
+    }
+
+    /// Counts the flags placed on tiles without a mine, including those already marked
+    /// as wrong by [`Board::reveal_all_mines`].
+    pub fn count_wrong_flags(&self) -> usize {
+        (0..self.tiles.len())
+            .filter(|&idx| match self.tiles[idx] {
+                Tile::WrongFlag => true,
+                Tile::Flagged => !self.is_mine(idx),
+                _ => false,
+            })
+            .count()
+    }
+
+    pub fn reveal_all_mines(&mut self, triggered_mines: &[usize]) {
+        for idx in 0..self.tiles.len() {
+            let is_mine = self.is_mine(idx);
+            let tile = &mut self.tiles[idx];
+
+            *tile = match &*tile {
+                _ if triggered_mines.contains(&idx) => Tile::TriggeredMine,
+                Tile::Flagged if !is_mine => Tile::WrongFlag,
+                Tile::Flagged => Tile::Flagged,
+                _ if is_mine => Tile::Mine,
+                tile => tile.clone(),
+            };
+        }
+    }
+}
+
+/// Parses a board from one line per row: `#` is a concealed tile, `*` a concealed mine,
+/// `F` a flag, `?` a question mark and digits are open tiles. `M` and `Q` are a flag and
+/// a question mark on a mine. The `@` of a triggered mine and the `X` of a wrong flag are
+/// read back as well, so a lost board written by the [`Display`](fmt::Display) impl can
+/// be parsed again.
+impl FromStr for Board {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s.trim().lines().map(str::trim).collect::<Vec<_>>();
+        let col_count = lines.first().map_or(0, |line| line.chars().count());
+
+        if col_count == 0 {
+            return Err(Error::Parse("The board is empty".to_string()));
+        }
+
+        let mut board = Board::new(lines.len(), col_count);
+        let mut mines = vec![false; lines.len() * col_count];
+
+        for (row, line) in lines.iter().enumerate() {
+            if line.chars().count() != col_count {
+                return Err(Error::Parse(format!(
+                    "Row {} has {} tiles instead of {}",
+                    row,
+                    line.chars().count(),
+                    col_count
+                )));
+            }
+
+            for (col, symbol) in line.chars().enumerate() {
+                let idx = coord_to_index(row, col, col_count);
+
+                board.tiles[idx] = match symbol {
+                    '#' => Tile::Concealed,
+                    'F' => Tile::Flagged,
+                    '?' => Tile::Question,
+                    'M' => {
+                        mines[idx] = true;
+                        Tile::Flagged
+                    }
+                    'Q' => {
+                        mines[idx] = true;
+                        Tile::Question
+                    }
+                    '*' => {
+                        mines[idx] = true;
+                        Tile::Concealed
+                    }
+                    '@' => {
+                        mines[idx] = true;
+                        Tile::TriggeredMine
+                    }
+                    'X' => Tile::WrongFlag,
+                    _ => match symbol.to_digit(10) {
+                        Some(count) => Tile::Open(count as usize),
+                        None => {
+                            return Err(Error::Parse(format!(
+                                "Unknown tile '{}' in row {}",
+                                symbol, row
+                            )))
+                        }
+                    },
+                };
+            }
+        }
+
+        board.mines = Some(mines);
+        board.update_mine_counts();
+        Ok(board)
+    }
+}
+
+/// Writes the board in the format read by [`Board::from_str`]. Revealed mines use the
+/// same symbols as the game: `*` for mines, `@` for the triggered mine and `X` for
+/// wrong flags.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (row, tiles) in self.tiles.chunks(self.col_count).enumerate() {
+            if row > 0 {
+                writeln!(f)?;
+            }
+
+            for (col, tile) in tiles.iter().enumerate() {
+                let idx = coord_to_index(row, col, self.col_count);
+
+                let symbol = match tile {
+                    Tile::Concealed if self.is_mine(idx) => "*".to_string(),
+                    Tile::Concealed => "#".to_string(),
+                    Tile::Flagged if self.is_mine(idx) => "M".to_string(),
+                    Tile::Flagged => "F".to_string(),
+                    Tile::Question if self.is_mine(idx) => "Q".to_string(),
+                    Tile::Question => "?".to_string(),
+                    Tile::Open(count) => count.to_string(),
+                    Tile::Mine => "*".to_string(),
+                    Tile::TriggeredMine => "@".to_string(),
+                    Tile::WrongFlag => "X".to_string(),
+                };
+                write!(f, "{}", symbol)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn coord_to_index(row: usize, col: usize, col_count: usize) -> usize {
+    Coord::new(row, col).to_index(col_count)
+}
+
+pub fn generate_mine_positions(
+    count: usize,
+    index_to_avoid: usize,
+    row_count: usize,
+    col_count: usize,
+    rng: &mut impl Rng,
+) -> Vec<bool> {
+    let mut indices_to_avoid = get_neighbouring_indices(index_to_avoid, row_count, col_count);
+    indices_to_avoid.insert(index_to_avoid);
+
+    choose_mine_positions(
+        count,
+        index_to_avoid,
+        indices_to_avoid,
+        row_count * col_count,
+        rng,
+    )
+}
+
+fn choose_mine_positions(
+    count: usize,
+    index_to_avoid: usize,
+    mut indices_to_avoid: HashSet<usize>,
+    tile_count: usize,
+    rng: &mut impl Rng,
+) -> Vec<bool> {
+    // Not enough room for a safe opening, so only keep the clicked tile free of mines.
+    if tile_count - indices_to_avoid.len() < count {
+        indices_to_avoid = HashSet::from_iter(vec![index_to_avoid]);
+    }
+
+    let mut mines = vec![false; tile_count];
+    for idx in (0..tile_count)
+        .filter(|idx| !indices_to_avoid.contains(idx))
+        .choose_multiple(rng, count)
+    {
+        mines[idx] = true;
+    }
+
+    mines
+}
+
+pub fn get_neighbouring_indices(
+    index: usize,
+    row_count: usize,
+    col_count: usize,
+) -> HashSet<usize> {
+    let Coord { row, col } = Coord::from_index(index, col_count);
+
+    let mut neighbours = HashSet::new();
+    for_each_neighbour(row, col, row_count, col_count, |idx| {
+        neighbours.insert(idx);
+    });
+
+    neighbours
+}
+
+/// Calls `f` with the index of every in-bounds neighbour of the tile at `row` and
+/// `col`, without allocating.
+pub fn for_each_neighbour(
+    row: usize,
+    col: usize,
+    row_count: usize,
+    col_count: usize,
+    mut f: impl FnMut(usize),
+) {
+    for neighbour_row in row.saturating_sub(1)..=(row + 1).min(row_count - 1) {
+        for neighbour_col in col.saturating_sub(1)..=(col + 1).min(col_count - 1) {
+            if neighbour_row == row && neighbour_col == col {
+                continue;
+            }
+
+            f(Coord::new(neighbour_row, neighbour_col).to_index(col_count));
+        }
+    }
+}
+
+/// Like [`get_neighbouring_indices`], but the edges of the board wrap around so every
+/// tile has up to eight neighbours.
+pub fn get_wrapping_neighbouring_indices(
+    index: usize,
+    row_count: usize,
+    col_count: usize,
+) -> HashSet<usize> {
+    let Coord { row, col } = Coord::from_index(index, col_count);
+
+    let mut neighbours = HashSet::new();
+    for_each_wrapping_neighbour(row, col, row_count, col_count, |idx| {
+        neighbours.insert(idx);
+    });
+
+    neighbours
+}
+
+/// Like [`for_each_neighbour`], but the edges of the board wrap around. On boards with
+/// fewer than three rows or columns each neighbour is still only passed to `f` once.
+pub fn for_each_wrapping_neighbour(
+    row: usize,
+    col: usize,
+    row_count: usize,
+    col_count: usize,
+    mut f: impl FnMut(usize),
+) {
+    let index = Coord::new(row, col).to_index(col_count);
+    let mut seen = [index; 8];
+    let mut seen_count = 0;
+
+    for row_offset in [row_count - 1, 0, 1] {
+        for col_offset in [col_count - 1, 0, 1] {
+            let neighbour_row = (row + row_offset) % row_count;
+            let neighbour_col = (col + col_offset) % col_count;
+            let neighbour = Coord::new(neighbour_row, neighbour_col).to_index(col_count);
+
+            if neighbour != index && !seen[..seen_count].contains(&neighbour) {
+                seen[seen_count] = neighbour;
+                seen_count += 1;
+                f(neighbour);
+            }
+        }
+    }
+}
+
+pub fn count_neighbouring_mines(
+    tile_idx: usize,
+    mines: &[bool],
+    row_count: usize,
+    col_count: usize,
+) -> usize {
+    let Coord { row, col } = Coord::from_index(tile_idx, col_count);
+
+    let mut mine_count = 0;
+    for_each_neighbour(row, col, row_count, col_count, |idx| {
+        if mines[idx] {
+            mine_count += 1;
+        }
+    });
+
+    mine_count
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn coord_to_index_on_a_non_square_board() {
+        // 3 rows of 5 columns.
+        assert_eq!(coord_to_index(0, 0, 5), 0);
+        assert_eq!(coord_to_index(0, 4, 5), 4);
+        assert_eq!(coord_to_index(2, 0, 5), 10);
+        assert_eq!(coord_to_index(2, 4, 5), 14);
+        assert_eq!(coord_to_index(1, 4, 5), 9);
+        assert_eq!(coord_to_index(2, 2, 5), 12);
+        assert_eq!(coord_to_index(1, 2, 5), 7);
+    }
+
+    #[test]
+    fn corner_tiles_have_three_neighbours_and_edge_tiles_five() {
+        let (row_count, col_count) = (4, 6);
+
+        for idx in [0, 5, 18, 23] {
+            assert_eq!(get_neighbouring_indices(idx, row_count, col_count).len(), 3);
+        }
+        for idx in [2, 6, 11, 20] {
+            assert_eq!(get_neighbouring_indices(idx, row_count, col_count).len(), 5);
+        }
+        assert_eq!(get_neighbouring_indices(8, row_count, col_count).len(), 8);
+        assert_eq!(
+            get_neighbouring_indices(0, row_count, col_count),
+            HashSet::from([1, 6, 7])
+        );
+    }
+
+    #[test]
+    fn first_click_area_is_free_of_mines() {
+        let (row_count, col_count) = (8, 8);
+
+        for seed in 0..200 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let first_click = (seed as usize * 7) % (row_count * col_count);
+            let mines = generate_mine_positions(50, first_click, row_count, col_count, &mut rng);
+
+            assert_eq!(mines.iter().filter(|&&mine| mine).count(), 50);
+            assert!(!mines[first_click]);
+            for idx in get_neighbouring_indices(first_click, row_count, col_count) {
+                assert!(
+                    !mines[idx],
+                    "seed {} placed a mine next to the first click",
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn large_board_without_mines_opens_from_the_corner() {
+        let mut board = Board::new(200, 200);
+        board.set_mines(&[]);
+
+        assert_eq!(board.open(0), OpenResult::Revealed);
+        assert_eq!(board.count_open(), 200 * 200);
+        assert!(board.tiles().iter().all(|tile| *tile == Tile::Open(0)));
+    }
+
+    #[test]
+    fn flood_fill_leaves_bordering_flags_alone() {
+        let mut board: Board = "F####\n#####\n###F*".parse().unwrap();
+
+        board.open(6);
+
+        assert_eq!(board.to_string(), "F0000\n00011\n000F*");
+    }
+
+    #[test]
+    fn corner_tile_has_eight_neighbours_when_wrapping() {
+        let mut board = Board::new(4, 5);
+        board.set_wrap(true);
+
+        assert_eq!(
+            board.neighbours(0),
+            HashSet::from([1, 4, 5, 6, 9, 15, 16, 19])
+        );
+        assert_eq!(board.neighbours(19).len(), 8);
+        assert_eq!(
+            get_wrapping_neighbouring_indices(0, 4, 5),
+            board.neighbours(0)
+        );
+    }
+
+    #[test]
+    fn three_bv_of_hand_built_boards() {
+        let single_region: Board = "*##\n###\n###".parse().unwrap();
+        assert_eq!(single_region.three_bv(), 1);
+
+        let two_regions: Board = "##*##\n##*##\n##*##".parse().unwrap();
+        assert_eq!(two_regions.three_bv(), 2);
+
+        let only_numbers: Board = "#*#\n*#*\n#*#".parse().unwrap();
+        assert_eq!(only_numbers.three_bv(), 5);
+
+        let opposite_corners: Board = "*####\n#####\n####*".parse().unwrap();
+        assert_eq!(opposite_corners.three_bv(), 1);
+    }
+
+    #[test]
+    fn boards_round_trip_through_their_text() {
+        for text in [
+            "*1#\n11#\n#F?",
+            "0000\n1221\n#**#",
+            "@2*\n12X\n000",
+            "M1Q\n121\n000",
+        ] {
+            let board: Board = text.parse().unwrap();
+            assert_eq!(board.to_string(), text);
+        }
+
+        let mut board: Board = "##*#\n*###\n####".parse().unwrap();
+        board.open(11);
+        board.flag(0, true);
+        board.flag(0, true);
+        board.flag(4, true);
+        assert_eq!(board.to_string(), "?#*#\nM211\n#100");
+        assert_eq!(board.to_string().parse::<Board>().unwrap(), board);
+        assert_eq!(board.to_string().parse::<Board>().unwrap().mine_count(), 2);
+
+        let mut lost: Board = "F#*\n###\n###".parse().unwrap();
+        lost.open(2);
+        assert_eq!(lost.to_string(), "X#@\n###\n###");
+        assert_eq!(lost.to_string().parse::<Board>().unwrap(), lost);
+    }
+
+    #[test]
+    fn ragged_rows_do_not_parse() {
+        assert!("###\n##\n###".parse::<Board>().is_err());
+        assert!("".parse::<Board>().is_err());
+        assert!("#!#".parse::<Board>().is_err());
+    }
+
+    #[test]
+    fn cached_mine_counts_match_the_counted_ones() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for (wrap, connectivity) in [
+            (false, Connectivity::Eight),
+            (true, Connectivity::Eight),
+            (false, Connectivity::Four),
+        ] {
+            let mut board = Board::new(30, 40);
+            board.set_wrap(wrap);
+            board.set_connectivity(connectivity);
+            board.generate_mines(300, 0, 1, &mut rng);
+
+            for idx in 0..board.tiles().len() {
+                assert_eq!(
+                    board.mine_counts[idx] as usize,
+                    board.count_neighbouring_mines(idx)
+                );
+            }
+        }
+
+        let mut board = Board::new(30, 40);
+        board.generate_mines(300, 0, 1, &mut rng);
+        let mines = board.mines.clone().unwrap();
+        for idx in 0..board.tiles().len() {
+            assert_eq!(
+                board.mine_counts[idx] as usize,
+                count_neighbouring_mines(idx, &mines, 30, 40)
+            );
+        }
+    }
+
+    #[test]
+    fn tiles_within_a_radius() {
+        let mut board = Board::new(5, 6);
+
+        assert!(board.tiles_within(14, 0).is_empty());
+        assert_eq!(board.tiles_within(0, 1), HashSet::from([0, 1, 6, 7]));
+        assert_eq!(board.tiles_within(14, 1).len(), 9);
+        assert_eq!(board.tiles_within(14, 2).len(), 25);
+        assert_eq!(board.tiles_within(14, 10).len(), 30);
+
+        board.set_wrap(true);
+        assert_eq!(board.tiles_within(0, 1).len(), 9);
+        assert!(board.tiles_within(0, 1).contains(&29));
+    }
+
+    #[test]
+    fn generated_mines_keep_the_safe_radius_free() {
+        for seed in 0..200 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut board = Board::new(9, 9);
+            board.generate_mines(10, 40, 1, &mut rng);
+
+            assert_eq!(board.mine_count(), 10);
+            for idx in board.tiles_within(40, 1) {
+                assert!(!board.is_mine(idx));
+            }
+        }
+    }
+
+    #[test]
+    fn coords_round_trip_through_indices() {
+        let (row_count, col_count) = (7, 3);
+
+        for idx in 0..row_count * col_count {
+            assert_eq!(Coord::from_index(idx, col_count).to_index(col_count), idx);
+        }
+
+        assert_eq!(Coord::from_index(2, col_count), Coord::new(0, 2));
+        assert_eq!(Coord::from_index(3, col_count), Coord::new(1, 0));
+        assert_eq!(Coord::from_index(20, col_count), Coord::new(6, 2));
+        assert_eq!(Coord::new(6, 2).to_index(col_count), 20);
+        assert_eq!(Coord::new(1, 0).to_index(col_count), 3);
+    }
+
+    #[test]
+    fn counts_neighbouring_mines() {
+        let empty: Board = "###\n###\n###".parse().unwrap();
+        assert!((0..9).all(|idx| empty.count_neighbouring_mines(idx) == 0));
+
+        let surrounded: Board = "***\n*#*\n***".parse().unwrap();
+        assert_eq!(surrounded.count_neighbouring_mines(4), 8);
+
+        let edges: Board = "#*##\n**##\n####\n##**".parse().unwrap();
+        assert_eq!(edges.count_neighbouring_mines(0), 3);
+        assert_eq!(edges.count_neighbouring_mines(3), 0);
+        assert_eq!(edges.count_neighbouring_mines(2), 2);
+        assert_eq!(edges.count_neighbouring_mines(8), 2);
+        assert_eq!(edges.count_neighbouring_mines(15), 1);
+        assert_eq!(edges.count_neighbouring_mines(11), 2);
+    }
+
+    #[test]
+    fn flood_fill_stops_at_the_numbers_around_a_walled_in_region() {
+        let mut board: Board = "*******\n\
+                                *#####*\n\
+                                *#####*\n\
+                                *#####*\n\
+                                *#####*\n\
+                                *#####*\n\
+                                *******"
+            .parse()
+            .unwrap();
+
+        board.open(24);
+
+        assert_eq!(
+            board.to_string(),
+            "*******\n\
+             *53335*\n\
+             *30003*\n\
+             *30003*\n\
+             *30003*\n\
+             *53335*\n\
+             *******"
+        );
+        assert_eq!(board.count_open(), 25);
+        assert!(board.is_won());
+    }
+
+    #[test]
+    fn reveal_in_waves_calls_back_after_every_wave() {
+        let mut board = Board::new(5, 5);
+        board.set_mines(&[]);
+        let mut open_counts = Vec::new();
+
+        board.reveal_in_waves(12, |board| open_counts.push(board.count_open()));
+
+        assert_eq!(open_counts, vec![1, 9, 25]);
+
+        let mut waved: Board = "#####\n#####\n####*".parse().unwrap();
+        let mut revealed = waved.clone();
+        let mut wave_count = 0;
+        waved.reveal_in_waves(0, |_| wave_count += 1);
+        revealed.reveal(0);
+
+        assert_eq!(waved, revealed);
+        assert_eq!(wave_count, 5);
+
+        let mut untouched: Board = "1*\n##".parse().unwrap();
+        untouched.reveal_in_waves(0, |_| panic!("nothing was opened"));
+    }
+
+    #[test]
+    fn limited_reveal_continues_to_the_full_flood_fill() {
+        let mut limited: Board = "#####\n#####\n####*".parse().unwrap();
+        let mut revealed = limited.clone();
+        revealed.reveal(0);
+
+        let mut frontier = VecDeque::from([0]);
+        assert_eq!(limited.reveal_limited(&mut frontier, 4), 4);
+        assert_eq!(limited.count_open(), 4);
+        assert!(!frontier.is_empty());
+
+        while !frontier.is_empty() {
+            limited.reveal_limited(&mut frontier, 4);
+        }
+        assert_eq!(limited, revealed);
+    }
+
+    #[test]
+    fn neighbour_summary_counts_corners_edges_and_the_middle() {
+        let board: Board = "1F##\n1?*#\n01##".parse().unwrap();
+        let summary = |tile_idx| {
+            let summary = board.neighbour_summary(tile_idx);
+            (summary.flagged, summary.concealed, summary.open)
+        };
+
+        // Corners
+        assert_eq!(summary(0), (1, 1, 1));
+        assert_eq!(summary(8), (0, 1, 2));
+        assert_eq!(summary(11), (0, 3, 0));
+        // Edges
+        assert_eq!(summary(1), (0, 3, 2));
+        assert_eq!(summary(4), (1, 1, 3));
+        // A tile with all eight neighbours
+        assert_eq!(summary(5), (1, 3, 4));
+    }
+
+    #[test]
+    fn restore_returns_to_the_snapshot() {
+        let mut board: Board = "*##\n###\n##*".parse().unwrap();
+        board.open(2);
+        let before = board.clone();
+        let snapshot = board.snapshot();
+
+        board.flag(0, false);
+        board.open(6);
+        board.open(8);
+        assert_ne!(board, before);
+
+        board.restore(&snapshot);
+        assert_eq!(board, before);
+        assert_eq!(board.changes_since(&snapshot), Vec::new());
+    }
+}
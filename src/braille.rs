@@ -0,0 +1,54 @@
+use cli_minesweeper::{Board, Tile};
+
+/// The tiles covered by one Braille character.
+pub const CELL_ROWS: usize = 4;
+pub const CELL_COLS: usize = 2;
+
+/// The bit of each dot in a Braille character, indexed by row and column within the cell.
+/// The first three rows were numbered down the left column before the fourth row was
+/// added, hence the odd order.
+const DOTS: [[u32; CELL_COLS]; CELL_ROWS] =
+    [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Renders the board zoomed out, one Braille character per 2x4 tiles. Every tile that
+/// is not open yet, including flags and mines, is a raised dot, so the opened areas
+/// show up as gaps. Returns one line per four rows of tiles.
+pub fn render(board: &Board) -> Vec<String> {
+    let line_count = board.row_count().div_ceil(CELL_ROWS);
+    let cell_count = board.col_count().div_ceil(CELL_COLS);
+    let mut cells = vec![vec![0; cell_count]; line_count];
+
+    for (row, col, tile) in board.iter_coords() {
+        if !matches!(tile, Tile::Open(_)) {
+            cells[row / CELL_ROWS][col / CELL_COLS] |= DOTS[row % CELL_ROWS][col % CELL_COLS];
+        }
+    }
+
+    cells
+        .iter()
+        .map(|line| {
+            line.iter()
+                .map(|&dots| char::from_u32(0x2800 + dots).unwrap_or(' '))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn board_renders_to_braille_cells() {
+        // Five rows and three columns leave a partly filled cell at the right and bottom.
+        let board: Board = "01#\n01F\n011\n000\n#00".parse().unwrap();
+
+        assert_eq!(
+            render(&board),
+            vec![
+                "\u{2800}\u{2803}".to_string(),
+                "\u{2801}\u{2800}".to_string()
+            ]
+        );
+    }
+}
@@ -0,0 +1,963 @@
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::SystemTime,
+};
+
+use cli_minesweeper::{Connectivity, Error};
+use serde::Deserialize;
+
+use crate::{
+    daily,
+    messages::{fill, Language, Messages},
+    share::BoardCode,
+};
+
+pub const USAGE: &str = "\
+Usage: cli-minesweeper [OPTIONS]
+
+Options:
+    --difficulty <level>    Use the beginner, intermediate or expert preset
+    --rows <count>          Number of rows on the board
+    --cols <count>          Number of columns on the board
+    --mines <count>         Number of mines on the board
+    --density <fraction>    Share of the tiles covered by mines, e.g. 0.2
+    --seed <number>         Seed for a reproducible mine layout
+    --code <code>           Play the board shared as a code by the 'code' command
+    --daily                 Play the daily challenge, the same intermediate board for everyone
+                            on the same UTC day
+    --lives <count>         Number of mines that can be hit before the game is lost (default: 1)
+    --versus                Two players take turns, hitting a mine loses and clearing the board
+                            wins for the player who opened more tiles
+    --no-guess              Only generate boards that can be solved without guessing
+    --first-click-safe <n>  Keep all tiles within n steps of the first click free of mines (default: 1)
+    --head-start <n>        Open n more random safe tiles after the first click, spread over
+                            the board
+    --easy-start            Regenerate the mines until the first click opens an area
+    --quiet                 Do not report the size of the opening on the first click, and
+                            with --script only print the final board
+    --no-question-marks     Only toggle between flagged and concealed tiles
+    --strict-flags          Do not allow more flags than there are mines
+    --wrap                  Let the board edges wrap around to the opposite side
+    --connectivity <n>      Count 4 (orthogonal) or 8 neighbours per tile (default: 8)
+    --load <file>           Continue a previously saved game
+    --script <file>         Run the commands in the file, one per line, without prompting.
+                            Ends on 'exit', the end of the file or the end of the game
+    --puzzle <file>         Play a fixed board from a text file, with '*' marking the mines
+    --log <file>            Write the game events to the file for debugging
+    --export-image <file>   Write the board as a PPM image to the file on game over
+    --record <file>         Write a replay of every move to the file on game over
+    --replay <file>         Play back a replay written by --record and print the result
+    --export-json <file>    Write the board, the moves and the result as JSON on game over
+    --replay-json <file>    Play back the moves of a game written by --export-json
+    --lang <language>       Language of the interface: en or de (default: from LANG)
+    --accessible            Describe the board row by row in words for screen readers
+    --compact               Draw the board zoomed out, 2x4 tiles per Braille character, to
+                            fit large boards on the screen
+    --color <when>          Colorize the board: auto, always or never
+    --coords <style>        Enter coordinates as numbers ('x 3 4') or letters ('x d3')
+    --no-clear              Print a separator line between turns instead of clearing the screen
+    --animate <ms>          Show the flood fill one wave at a time, waiting ms between waves
+    --reveal-budget <n>     Open at most n tiles per move, 'continue' opens the next ones
+    --incremental           Redraw only the changed tiles instead of clearing the screen
+    --last-action           Show the last move and how many tiles it revealed below the status
+    --progress              Show how many of the safe tiles are opened in the status line
+    --separator <text>      Text printed between the board columns (default: ' ')
+    --zero-char <char>      Symbol for opened tiles without neighbouring mines (default: ' ')
+    --number-colors <map>   Colors of the numbers, e.g. '4=magenta,7=white'. Colors: black,
+                            red, green, yellow, blue, magenta, cyan, white, gray and their
+                            bright- variants (default: the classic Windows palette)
+    --symbols <list>        Symbols for concealed, flagged, question marked and mine tiles,
+                            separated by commas (default: '#,F,?,*'). Symbols are aligned by
+                            their character count, so wide glyphs like emoji should all be
+                            wide
+    --scores                Print the high score table and exit
+
+Defaults for rows, cols, mines, color, coords, symbols and number-colors can be set in
+./minesweeper.toml or in minesweeper.toml in the config directory, using the option names
+as keys, e.g. 'rows = 16' or 'symbols = \"#,F,?,*\"'. Options on the command line take
+precedence over the file.
+
+Ctrl-C saves a running game to cli-minesweeper/autosave.json in the config directory
+(~/.config on Linux), continue it with '--load <file>'.";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [
+        Difficulty::Beginner,
+        Difficulty::Intermediate,
+        Difficulty::Expert,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Intermediate => "intermediate",
+            Difficulty::Expert => "expert",
+        }
+    }
+
+    pub fn dimensions(&self) -> (usize, usize, usize) {
+        match self {
+            Difficulty::Beginner => (9, 9, 10),
+            Difficulty::Intermediate => (16, 16, 40),
+            Difficulty::Expert => (16, 30, 99),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub fn enabled(&self) -> bool {
+        match self {
+            ColorMode::Auto => io::stdout().is_terminal(),
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CoordStyle {
+    Numbers,
+    Letters,
+}
+
+/// The symbols drawn for the tiles that do not show a number.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Symbols {
+    pub concealed: String,
+    pub flagged: String,
+    pub question: String,
+    pub mine: String,
+}
+
+impl Default for Symbols {
+    fn default() -> Self {
+        Symbols {
+            concealed: "#".to_string(),
+            flagged: "F".to_string(),
+            question: "?".to_string(),
+            mine: "*".to_string(),
+        }
+    }
+}
+
+/// The ANSI color codes of the numbers 1 to 8.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NumberColors([&'static str; 8]);
+
+impl NumberColors {
+    pub fn color(&self, mine_count: usize) -> Option<&'static str> {
+        mine_count
+            .checked_sub(1)
+            .and_then(|idx| self.0.get(idx))
+            .copied()
+    }
+}
+
+impl Default for NumberColors {
+    /// The classic Windows palette: blue, green, red, navy, maroon, teal, black and gray.
+    fn default() -> Self {
+        NumberColors(["94", "32", "91", "34", "31", "36", "30", "90"])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub row_count: usize,
+    pub col_count: usize,
+    pub mine_count: usize,
+    pub seed: Option<u64>,
+    pub code: Option<BoardCode>,
+    pub daily: bool,
+    pub question_marks: bool,
+    pub strict_flags: bool,
+    pub wrap: bool,
+    pub connectivity: Connectivity,
+    pub load_path: Option<String>,
+    pub script_path: Option<String>,
+    pub puzzle_path: Option<String>,
+    pub log_path: Option<String>,
+    pub export_image: Option<String>,
+    pub record_path: Option<String>,
+    pub replay_path: Option<String>,
+    pub export_json: Option<String>,
+    pub replay_json: Option<String>,
+    pub show_scores: bool,
+    pub autoplay: bool,
+    pub tui: bool,
+    pub color: ColorMode,
+    pub accessible: bool,
+    pub compact: bool,
+    pub coords: CoordStyle,
+    pub language: Language,
+    pub separator: String,
+    pub clear_screen: bool,
+    pub animate: Option<u64>,
+    pub reveal_budget: Option<usize>,
+    pub incremental: bool,
+    pub last_action: bool,
+    pub progress: bool,
+    pub zero_char: char,
+    pub symbols: Symbols,
+    pub number_colors: NumberColors,
+    pub lives: usize,
+    pub versus: bool,
+    pub no_guess: bool,
+    pub easy_start: bool,
+    pub head_start: usize,
+    pub first_click_safe: usize,
+    pub quiet: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            row_count: 10,
+            col_count: 5,
+            mine_count: 1,
+            seed: None,
+            code: None,
+            daily: false,
+            question_marks: true,
+            strict_flags: false,
+            wrap: false,
+            connectivity: Connectivity::Eight,
+            load_path: None,
+            script_path: None,
+            puzzle_path: None,
+            log_path: None,
+            export_image: None,
+            record_path: None,
+            replay_path: None,
+            export_json: None,
+            replay_json: None,
+            show_scores: false,
+            autoplay: false,
+            tui: false,
+            color: ColorMode::Auto,
+            accessible: false,
+            compact: false,
+            coords: CoordStyle::Numbers,
+            language: Language::default(),
+            separator: " ".to_string(),
+            clear_screen: true,
+            animate: None,
+            reveal_budget: None,
+            incremental: false,
+            last_action: false,
+            progress: false,
+            zero_char: ' ',
+            symbols: Symbols::default(),
+            number_colors: NumberColors::default(),
+            lives: 1,
+            versus: false,
+            no_guess: false,
+            easy_start: false,
+            head_start: 0,
+            first_click_safe: 1,
+            quiet: false,
+        }
+    }
+}
+
+/// Defaults read from the config file, each of them can be overridden on the command line.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct FileConfig {
+    rows: Option<usize>,
+    cols: Option<usize>,
+    mines: Option<usize>,
+    color: Option<String>,
+    coords: Option<String>,
+    symbols: Option<String>,
+    number_colors: Option<String>,
+}
+
+impl FileConfig {
+    /// Applies the first config file found to `config`, a missing file leaves it untouched.
+    fn load(config: &mut Config, messages: &Messages) -> Result<(), Error> {
+        let Some(path) = config_file_paths().into_iter().find(|path| path.exists()) else {
+            return Ok(());
+        };
+
+        Self::read(&path, messages)?
+            .apply(config, messages)
+            .map_err(|error| {
+                Error::Config(fill(
+                    messages.config_file_invalid,
+                    &[&path.display(), &error],
+                ))
+            })
+    }
+
+    fn read(path: &Path, messages: &Messages) -> Result<Self, Error> {
+        let content = fs::read_to_string(path)?;
+
+        toml::from_str(&content).map_err(|error| {
+            Error::Config(fill(
+                messages.config_file_invalid,
+                &[&path.display(), &error.message()],
+            ))
+        })
+    }
+
+    /// Overwrites the built-in defaults in `config` with the values from the file.
+    fn apply(self, config: &mut Config, messages: &Messages) -> Result<(), Error> {
+        config.row_count = self.rows.unwrap_or(config.row_count);
+        config.col_count = self.cols.unwrap_or(config.col_count);
+        config.mine_count = self.mines.unwrap_or(config.mine_count);
+
+        if self.color.is_some() {
+            config.color = parse_color("color", self.color, messages)?;
+        }
+        if self.coords.is_some() {
+            config.coords = parse_coord_style("coords", self.coords, messages)?;
+        }
+        if self.symbols.is_some() {
+            config.symbols = parse_symbols("symbols", self.symbols, messages)?;
+        }
+        if self.number_colors.is_some() {
+            config.number_colors =
+                parse_number_colors("number-colors", self.number_colors, messages)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The config file in the working directory comes first, then the one in the user's
+/// config directory.
+fn config_file_paths() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from("minesweeper.toml")];
+
+    if let Some(config_dir) = dirs::config_dir() {
+        paths.push(config_dir.join("cli-minesweeper").join("minesweeper.toml"));
+    }
+
+    paths
+}
+
+pub fn parse_args(args: impl Iterator<Item = String>) -> Result<Config, Error> {
+    let args = args.collect::<Vec<_>>();
+    let messages = language_of(&args).messages();
+    let mut defaults = Config::default();
+    FileConfig::load(&mut defaults, messages)?;
+
+    parse_args_over(args.into_iter(), defaults, messages)
+}
+
+/// The language picked by `--lang`, looked up before the other arguments so that their
+/// errors are reported in it.
+fn language_of(args: &[String]) -> Language {
+    args.windows(2)
+        .find(|pair| pair[0] == "--lang")
+        .and_then(|pair| pair[1].parse().ok())
+        .unwrap_or_else(Language::from_env)
+}
+
+/// Parses the arguments on top of `defaults`, the built-in defaults overwritten by the
+/// config file.
+fn parse_args_over(
+    mut args: impl Iterator<Item = String>,
+    defaults: Config,
+    messages: &Messages,
+) -> Result<Config, Error> {
+    let mut difficulty = None;
+    let mut row_count = None;
+    let mut col_count = None;
+    let mut mine_count = None;
+    let mut density = None;
+    let mut seed = None;
+    let mut code = None;
+    let mut daily = false;
+    let mut question_marks = true;
+    let mut strict_flags = false;
+    let mut wrap = false;
+    let mut connectivity = Connectivity::Eight;
+    let mut load_path = None;
+    let mut script_path = None;
+    let mut puzzle_path = None;
+    let mut log_path = None;
+    let mut export_image = None;
+    let mut record_path = None;
+    let mut replay_path = None;
+    let mut export_json = None;
+    let mut replay_json = None;
+    let mut show_scores = false;
+    let mut autoplay = false;
+    let mut tui = false;
+    let mut accessible = false;
+    let mut compact = false;
+    let mut color = None;
+    let mut coords = None;
+    let mut language = None;
+    let mut separator = None;
+    let mut no_clear = false;
+    let mut animate = None;
+    let mut reveal_budget = None;
+    let mut incremental = false;
+    let mut last_action = false;
+    let mut progress = false;
+    let mut zero_char = ' ';
+    let mut symbols = None;
+    let mut number_colors = None;
+    let mut lives = 1;
+    let mut versus = false;
+    let mut no_guess = false;
+    let mut easy_start = false;
+    let mut head_start = 0;
+    let mut first_click_safe = None;
+    let mut quiet = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--difficulty" => difficulty = Some(parse_difficulty(&arg, args.next(), messages)?),
+            "--rows" => row_count = Some(parse_value(&arg, args.next(), messages)?),
+            "--cols" => col_count = Some(parse_value(&arg, args.next(), messages)?),
+            "--mines" => mine_count = Some(parse_value(&arg, args.next(), messages)?),
+            "--density" => density = Some(parse_density(&arg, args.next(), messages)?),
+            "--seed" => seed = Some(parse_value(&arg, args.next(), messages)?),
+            "--code" => code = Some(parse_value::<BoardCode>(&arg, args.next(), messages)?),
+            "--daily" => daily = true,
+            "--no-question-marks" => question_marks = false,
+            "--strict-flags" => strict_flags = true,
+            "--wrap" => wrap = true,
+            "--connectivity" => connectivity = parse_connectivity(&arg, args.next(), messages)?,
+            "--lives" => {
+                lives = parse_value(&arg, args.next(), messages)?;
+                if lives == 0 {
+                    return Err(Error::Config(messages.lives_zero.to_string()));
+                }
+            }
+            "--versus" => versus = true,
+            "--no-guess" => no_guess = true,
+            "--easy-start" => easy_start = true,
+            "--head-start" => head_start = parse_value(&arg, args.next(), messages)?,
+            "--first-click-safe" => {
+                first_click_safe = Some(parse_value(&arg, args.next(), messages)?)
+            }
+            "--quiet" => quiet = true,
+            "--load" => load_path = Some(parse_value(&arg, args.next(), messages)?),
+            "--script" => script_path = Some(parse_value(&arg, args.next(), messages)?),
+            "--puzzle" => puzzle_path = Some(parse_value(&arg, args.next(), messages)?),
+            "--log" => log_path = Some(parse_value(&arg, args.next(), messages)?),
+            "--export-image" => export_image = Some(parse_value(&arg, args.next(), messages)?),
+            "--record" => record_path = Some(parse_value(&arg, args.next(), messages)?),
+            "--replay" => replay_path = Some(parse_value(&arg, args.next(), messages)?),
+            "--export-json" => export_json = Some(parse_value(&arg, args.next(), messages)?),
+            "--replay-json" => replay_json = Some(parse_value(&arg, args.next(), messages)?),
+            "--scores" => show_scores = true,
+            "--autoplay" => autoplay = true,
+            "--tui" => tui = true,
+            "--accessible" => accessible = true,
+            "--compact" => compact = true,
+            "--color" => color = Some(parse_color(&arg, args.next(), messages)?),
+            "--coords" => coords = Some(parse_coord_style(&arg, args.next(), messages)?),
+            "--lang" => language = Some(parse_value(&arg, args.next(), messages)?),
+            "--no-clear" => no_clear = true,
+            "--animate" => animate = Some(parse_value(&arg, args.next(), messages)?),
+            "--reveal-budget" => {
+                reveal_budget = Some(parse_value(&arg, args.next(), messages)?);
+                if reveal_budget == Some(0) {
+                    return Err(Error::Config(messages.reveal_budget_zero.to_string()));
+                }
+            }
+            "--incremental" => incremental = true,
+            "--last-action" => last_action = true,
+            "--progress" => progress = true,
+            "--separator" => separator = Some(parse_value(&arg, args.next(), messages)?),
+            "--zero-char" => zero_char = parse_value(&arg, args.next(), messages)?,
+            "--symbols" => symbols = Some(parse_symbols(&arg, args.next(), messages)?),
+            "--number-colors" => {
+                number_colors = Some(parse_number_colors(&arg, args.next(), messages)?)
+            }
+            _ => return Err(Error::Config(fill(messages.unknown_argument, &[&arg]))),
+        }
+    }
+
+    if load_path.is_some() && puzzle_path.is_some() {
+        return Err(Error::Config(messages.load_with_puzzle.to_string()));
+    }
+
+    if daily && (seed.is_some() || code.is_some() || load_path.is_some() || puzzle_path.is_some()) {
+        return Err(Error::Config(messages.daily_conflict.to_string()));
+    }
+
+    // The TUI places the mines on its own first click, it cannot open the fixed first
+    // click of a shared board.
+    if tui && (daily || code.is_some()) {
+        return Err(Error::Config(messages.tui_conflict.to_string()));
+    }
+
+    if versus && (tui || autoplay) {
+        return Err(Error::Config(messages.versus_conflict.to_string()));
+    }
+
+    if reveal_budget.is_some() && (tui || versus) {
+        return Err(Error::Config(messages.reveal_budget_conflict.to_string()));
+    }
+
+    if head_start > 0 && (tui || versus || reveal_budget.is_some()) {
+        return Err(Error::Config(messages.head_start_conflict.to_string()));
+    }
+
+    if script_path.is_some() && (tui || incremental) {
+        return Err(Error::Config(messages.script_conflict.to_string()));
+    }
+
+    if record_path.is_some() && (load_path.is_some() || puzzle_path.is_some()) {
+        return Err(Error::Config(messages.record_conflict.to_string()));
+    }
+
+    let mut config = defaults;
+
+    if let Some(difficulty) = difficulty {
+        (config.row_count, config.col_count, config.mine_count) = difficulty.dimensions();
+
+        if row_count.is_some() || col_count.is_some() || mine_count.is_some() || density.is_some() {
+            println!(
+                "{}",
+                fill(messages.difficulty_override, &[&difficulty.label()])
+            );
+        }
+    }
+
+    config.row_count = row_count.unwrap_or(config.row_count);
+    config.col_count = col_count.unwrap_or(config.col_count);
+    config.mine_count = mine_count.unwrap_or(config.mine_count);
+    config.seed = seed;
+    config.question_marks = question_marks;
+    config.strict_flags = strict_flags;
+    config.wrap = wrap;
+    config.connectivity = connectivity;
+    config.load_path = load_path;
+    config.puzzle_path = puzzle_path;
+    config.log_path = log_path;
+    config.export_image = export_image;
+    config.record_path = record_path;
+    config.replay_path = replay_path;
+    config.export_json = export_json;
+    config.replay_json = replay_json;
+    config.show_scores = show_scores;
+    config.autoplay = autoplay;
+    config.tui = tui;
+    config.color = color.unwrap_or(config.color);
+    config.coords = coords.unwrap_or(config.coords);
+    config.language = language.unwrap_or_else(Language::from_env);
+    config.separator = separator.unwrap_or(config.separator);
+    // Clearing a redirected output or the turns of a script would only fill a log with
+    // escape sequences.
+    config.clear_screen = !no_clear && io::stdout().is_terminal() && script_path.is_none();
+    config.script_path = script_path;
+    config.animate = animate;
+    config.reveal_budget = reveal_budget;
+    config.incremental = incremental;
+    // The board alone does not tell a screen reader user what just changed.
+    config.last_action = last_action || accessible;
+
+    // Screen readers are best served by plain lines, so drop everything that relies on
+    // escape sequences.
+    if accessible {
+        if tui {
+            return Err(Error::Config(messages.accessible_conflict.to_string()));
+        }
+
+        config.accessible = true;
+        config.color = ColorMode::Never;
+        config.clear_screen = false;
+        config.incremental = false;
+    }
+
+    if compact && (tui || accessible || incremental) {
+        return Err(Error::Config(messages.compact_conflict.to_string()));
+    }
+    config.compact = compact;
+    config.progress = progress;
+    config.zero_char = zero_char;
+    config.symbols = symbols.unwrap_or(config.symbols);
+    config.number_colors = number_colors.unwrap_or(config.number_colors);
+    config.lives = lives;
+    config.versus = versus;
+    config.no_guess = no_guess;
+    config.easy_start = easy_start;
+    config.head_start = head_start;
+    config.first_click_safe = first_click_safe.unwrap_or(config.first_click_safe);
+    config.quiet = quiet;
+
+    if let Some(density) = density {
+        if mine_count.is_some() {
+            return Err(Error::Config(messages.mines_with_density.to_string()));
+        }
+
+        let tile_count = tile_count(config.row_count, config.col_count, messages)?;
+        let mine_count = (tile_count as f64 * density).round() as usize;
+        config.mine_count = mine_count.min(max_mine_count(
+            config.row_count,
+            config.col_count,
+            config.first_click_safe,
+        ));
+    }
+
+    if daily {
+        code = Some(daily::board_code(SystemTime::now()));
+        config.daily = true;
+    }
+
+    if let Some(code) = code {
+        code.apply(&mut config);
+        config.code = Some(code);
+    }
+
+    validate_config(
+        config.row_count,
+        config.col_count,
+        config.mine_count,
+        config.first_click_safe,
+        messages,
+    )?;
+
+    // One head start tile per ten tiles helps without playing the game for the player.
+    let max_head_start = config.row_count * config.col_count / 10;
+    if config.head_start > max_head_start {
+        return Err(Error::Config(fill(
+            messages.head_start_too_large,
+            &[&max_head_start, &config.row_count, &config.col_count],
+        )));
+    }
+
+    Ok(config)
+}
+
+/// Checks that the board has tiles and that the mines fit next to the tiles kept free
+/// around the first click.
+pub fn validate_config(
+    row_count: usize,
+    col_count: usize,
+    mine_count: usize,
+    first_click_safe: usize,
+    messages: &Messages,
+) -> Result<(), Error> {
+    if row_count == 0 || col_count == 0 {
+        return Err(Error::Config(fill(
+            messages.board_empty,
+            &[&row_count, &col_count],
+        )));
+    }
+
+    let tile_count = tile_count(row_count, col_count, messages)?;
+    if mine_count >= tile_count {
+        return Err(Error::Config(fill(
+            messages.too_many_mines,
+            &[&mine_count, &tile_count],
+        )));
+    }
+
+    if mine_count > max_mine_count(row_count, col_count, first_click_safe) {
+        let safe_tile_count = safe_tile_count(row_count, col_count, first_click_safe);
+        return Err(Error::Config(fill(
+            messages.mines_do_not_fit,
+            &[&mine_count, &safe_tile_count, &first_click_safe],
+        )));
+    }
+
+    Ok(())
+}
+
+/// The number of tiles on the board, or an error if it does not fit into a `usize`.
+fn tile_count(row_count: usize, col_count: usize, messages: &Messages) -> Result<usize, Error> {
+    row_count
+        .checked_mul(col_count)
+        .ok_or_else(|| Error::Config(fill(messages.board_too_large, &[&row_count, &col_count])))
+}
+
+/// The most mines that fit on the board next to the tiles kept free around the first
+/// click, leaving at least one tile without a mine.
+fn max_mine_count(row_count: usize, col_count: usize, first_click_safe: usize) -> usize {
+    let tile_count = row_count * col_count;
+
+    (tile_count - safe_tile_count(row_count, col_count, first_click_safe))
+        .min(tile_count.saturating_sub(1))
+}
+
+/// The number of tiles kept free of mines around the first click in the worst case.
+fn safe_tile_count(row_count: usize, col_count: usize, first_click_safe: usize) -> usize {
+    let safe_width = first_click_safe.saturating_mul(2).saturating_add(1);
+
+    match first_click_safe {
+        0 => 0,
+        _ => safe_width.min(row_count) * safe_width.min(col_count),
+    }
+}
+
+fn parse_value<T: FromStr>(
+    arg: &str,
+    value: Option<String>,
+    messages: &Messages,
+) -> Result<T, Error> {
+    let value = value.ok_or_else(|| Error::Config(fill(messages.missing_value, &[&arg])))?;
+
+    value
+        .parse::<T>()
+        .map_err(|_| invalid_value(arg, &value, messages))
+}
+
+fn parse_density(arg: &str, value: Option<String>, messages: &Messages) -> Result<f64, Error> {
+    let density: f64 = parse_value(arg, value.clone(), messages)?;
+
+    if !(0.0..=1.0).contains(&density) {
+        return Err(invalid_value(arg, &value.unwrap_or_default(), messages));
+    }
+
+    Ok(density)
+}
+
+fn parse_difficulty(
+    arg: &str,
+    value: Option<String>,
+    messages: &Messages,
+) -> Result<Difficulty, Error> {
+    let value = value.ok_or_else(|| Error::Config(fill(messages.missing_value, &[&arg])))?;
+
+    Difficulty::ALL
+        .into_iter()
+        .find(|difficulty| difficulty.label() == value.to_lowercase())
+        .ok_or_else(|| invalid_value(arg, &value, messages))
+}
+
+fn parse_color(arg: &str, value: Option<String>, messages: &Messages) -> Result<ColorMode, Error> {
+    let value = value.ok_or_else(|| Error::Config(fill(messages.missing_value, &[&arg])))?;
+
+    match value.to_lowercase().as_str() {
+        "auto" => Ok(ColorMode::Auto),
+        "always" => Ok(ColorMode::Always),
+        "never" => Ok(ColorMode::Never),
+        _ => Err(invalid_value(arg, &value, messages)),
+    }
+}
+
+fn parse_coord_style(
+    arg: &str,
+    value: Option<String>,
+    messages: &Messages,
+) -> Result<CoordStyle, Error> {
+    let value = value.ok_or_else(|| Error::Config(fill(messages.missing_value, &[&arg])))?;
+
+    match value.to_lowercase().as_str() {
+        "numbers" => Ok(CoordStyle::Numbers),
+        "letters" => Ok(CoordStyle::Letters),
+        _ => Err(invalid_value(arg, &value, messages)),
+    }
+}
+
+fn parse_connectivity(
+    arg: &str,
+    value: Option<String>,
+    messages: &Messages,
+) -> Result<Connectivity, Error> {
+    let count = parse_value(arg, value.clone(), messages)?;
+
+    Connectivity::from_neighbour_count(count)
+        .ok_or_else(|| invalid_value(arg, &value.unwrap_or_default(), messages))
+}
+
+fn parse_symbols(arg: &str, value: Option<String>, messages: &Messages) -> Result<Symbols, Error> {
+    let value = value.ok_or_else(|| Error::Config(fill(messages.missing_value, &[&arg])))?;
+
+    match value.split(',').collect::<Vec<_>>()[..] {
+        [concealed, flagged, question, mine]
+            if [concealed, flagged, question, mine]
+                .iter()
+                .all(|symbol| !symbol.is_empty()) =>
+        {
+            Ok(Symbols {
+                concealed: concealed.to_string(),
+                flagged: flagged.to_string(),
+                question: question.to_string(),
+                mine: mine.to_string(),
+            })
+        }
+        _ => Err(invalid_value(arg, &value, messages)),
+    }
+}
+
+fn parse_number_colors(
+    arg: &str,
+    value: Option<String>,
+    messages: &Messages,
+) -> Result<NumberColors, Error> {
+    let value = value.ok_or_else(|| Error::Config(fill(messages.missing_value, &[&arg])))?;
+    let mut number_colors = NumberColors::default();
+
+    for entry in value.split(',') {
+        let invalid = || invalid_value(arg, entry, messages);
+        let (number, color) = entry.split_once('=').ok_or_else(invalid)?;
+
+        let number = number
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|number| (1..=8).contains(number))
+            .ok_or_else(invalid)?;
+        let code = color_code(color.trim())
+            .ok_or_else(|| Error::Config(fill(messages.unknown_color, &[&color.trim(), &arg])))?;
+
+        number_colors.0[number - 1] = code;
+    }
+
+    Ok(number_colors)
+}
+
+fn color_code(name: &str) -> Option<&'static str> {
+    let code = match name.to_lowercase().as_str() {
+        "black" => "30",
+        "red" => "31",
+        "green" => "32",
+        "yellow" => "33",
+        "blue" => "34",
+        "magenta" => "35",
+        "cyan" => "36",
+        "white" => "37",
+        "gray" | "grey" => "90",
+        "bright-red" => "91",
+        "bright-green" => "92",
+        "bright-yellow" => "93",
+        "bright-blue" => "94",
+        "bright-magenta" => "95",
+        "bright-cyan" => "96",
+        "bright-white" => "97",
+        _ => return None,
+    };
+
+    Some(code)
+}
+
+fn invalid_value(arg: &str, value: &str, messages: &Messages) -> Error {
+    Error::Config(fill(messages.invalid_value, &[&value, &arg]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::{ENGLISH, GERMAN};
+
+    #[test]
+    fn mines_have_to_fit_next_to_the_safe_area() {
+        // A radius of 2 keeps 5x5 tiles free on a 10x10 board.
+        assert!(validate_config(10, 10, 75, 2, &ENGLISH).is_ok());
+        assert!(validate_config(10, 10, 76, 2, &ENGLISH).is_err());
+        assert!(validate_config(10, 10, 91, 1, &ENGLISH).is_ok());
+        assert!(validate_config(10, 10, 92, 1, &ENGLISH).is_err());
+
+        // The safe area is cut off by the edges of a small board.
+        assert!(validate_config(3, 3, 1, 2, &ENGLISH).is_err());
+        assert!(validate_config(3, 3, 8, 0, &ENGLISH).is_ok());
+    }
+
+    #[test]
+    fn validate_config_boundaries() {
+        assert!(validate_config(0, 5, 1, 1, &ENGLISH).is_err());
+        assert!(validate_config(5, 0, 1, 1, &ENGLISH).is_err());
+        assert!(validate_config(0, 0, 0, 0, &ENGLISH).is_err());
+
+        // Without a safe area every tile but one may be a mine.
+        assert!(validate_config(4, 5, 19, 0, &ENGLISH).is_ok());
+        assert!(validate_config(4, 5, 20, 0, &ENGLISH).is_err());
+
+        // The default radius of 1 keeps 3x3 tiles free.
+        assert!(validate_config(4, 5, 11, 1, &ENGLISH).is_ok());
+        assert!(validate_config(4, 5, 12, 1, &ENGLISH).is_err());
+        assert!(validate_config(1, 1, 0, 1, &ENGLISH).is_ok());
+        assert!(validate_config(1, 1, 1, 0, &ENGLISH).is_err());
+    }
+
+    #[test]
+    fn huge_boards_give_a_clean_error() {
+        assert!(matches!(
+            validate_config(usize::MAX, 2, 1, 1, &ENGLISH),
+            Err(Error::Config(_))
+        ));
+        assert!(matches!(
+            validate_config(usize::MAX / 2 + 1, usize::MAX / 2 + 1, 1, 1, &ENGLISH),
+            Err(Error::Config(_))
+        ));
+        assert!(matches!(
+            validate_config(1, usize::MAX, usize::MAX, 0, &ENGLISH),
+            Err(Error::Config(_))
+        ));
+        assert!(tile_count(usize::MAX, 1, &ENGLISH).is_ok());
+        assert!(tile_count(usize::MAX, 2, &ENGLISH).is_err());
+    }
+
+    fn parse(args: &[&str], file: &str) -> Result<Config, Error> {
+        let args = args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let messages = language_of(&args).messages();
+        let mut defaults = Config::default();
+        toml::from_str::<FileConfig>(file)
+            .unwrap()
+            .apply(&mut defaults, messages)?;
+
+        parse_args_over(args.into_iter(), defaults, messages)
+    }
+
+    #[test]
+    fn file_overrides_the_defaults_and_arguments_override_the_file() {
+        let file = "rows = 12\nmines = 20\ncoords = \"letters\"\n";
+
+        let config = parse(&[], file).unwrap();
+        assert_eq!(config.row_count, 12);
+        assert_eq!(config.col_count, Config::default().col_count);
+        assert_eq!(config.mine_count, 20);
+        assert_eq!(config.coords, CoordStyle::Letters);
+
+        let config = parse(&["--mines", "7", "--coords", "numbers"], file).unwrap();
+        assert_eq!(config.row_count, 12);
+        assert_eq!(config.mine_count, 7);
+        assert_eq!(config.coords, CoordStyle::Numbers);
+
+        let config = parse(&["--rows", "8"], "").unwrap();
+        assert_eq!(config.row_count, 8);
+        assert_eq!(config.mine_count, Config::default().mine_count);
+    }
+
+    #[test]
+    fn invalid_file_values_are_errors() {
+        assert!(parse(&[], "color = \"sometimes\"").is_err());
+        assert!(toml::from_str::<FileConfig>("unknown = 1").is_err());
+    }
+
+    #[test]
+    fn tui_rejects_a_fixed_first_click() {
+        assert!(parse(&["--tui", "--daily"], "").is_err());
+        assert!(parse(&["--tui", "--code", "9-9-a-1-0-28-1"], "").is_err());
+        assert!(parse(&["--code", "9-9-a-1-0-28-1"], "").is_ok());
+    }
+
+    #[test]
+    fn errors_are_reported_in_the_selected_language() {
+        let Err(Error::Config(message)) = parse(&["--lang", "de", "--lives", "0"], "") else {
+            panic!("expected a config error");
+        };
+        assert_eq!(message, GERMAN.lives_zero);
+
+        let Err(Error::Config(message)) = parse(&["--lives", "0", "--lang", "en"], "") else {
+            panic!("expected a config error");
+        };
+        assert_eq!(message, ENGLISH.lives_zero);
+    }
+}
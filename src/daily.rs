@@ -0,0 +1,105 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cli_minesweeper::{Connectivity, Coord};
+
+use crate::{config::Difficulty, share::BoardCode};
+
+/// The board of the daily challenge: the intermediate preset seeded with the UTC date as
+/// `YYYYMMDD`. The mines depend on the first click, so the first click is fixed as well:
+/// the centre of the board is opened for every player before the first move.
+pub fn board_code(now: SystemTime) -> BoardCode {
+    let (row_count, col_count, mine_count) = Difficulty::Intermediate.dimensions();
+
+    BoardCode {
+        row_count,
+        col_count,
+        mine_count,
+        first_click_safe: 1,
+        wrap: false,
+        connectivity: Connectivity::Eight,
+        no_guess: false,
+        easy_start: false,
+        seed: date_seed(now),
+        first_click: Coord::new(row_count / 2, col_count / 2).to_index(col_count),
+    }
+}
+
+/// The UTC date of `now` as the number `YYYYMMDD`.
+pub fn date_seed(now: SystemTime) -> u64 {
+    let days = now
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() / 86_400);
+    let (year, month, day) = civil_from_days(days);
+
+    year * 10_000 + month * 100 + day
+}
+
+/// Converts the days since 1970-01-01 to a year, month and day in the proleptic
+/// Gregorian calendar, following Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use cli_minesweeper::GameState;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::config::Config;
+
+    /// 2024-02-29 12:00 UTC.
+    const LEAP_DAY_NOON: u64 = 1_709_208_000;
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    fn daily_board(now: SystemTime) -> GameState {
+        let code = board_code(now);
+        let mut config = Config::default();
+        code.apply(&mut config);
+
+        let mut state = GameState::new(config.row_count, config.col_count, config.mine_count);
+        let mut rng = StdRng::seed_from_u64(code.seed);
+        crate::open_tile(&mut state, code.first_click, &config, &mut rng, |_| {});
+        state
+    }
+
+    #[test]
+    fn date_seed_is_the_utc_date() {
+        assert_eq!(date_seed(UNIX_EPOCH), 19_700_101);
+        assert_eq!(date_seed(at(LEAP_DAY_NOON)), 20_240_229);
+        assert_eq!(date_seed(at(LEAP_DAY_NOON + 12 * 3600)), 20_240_301);
+        assert_eq!(date_seed(at(LEAP_DAY_NOON + 12 * 3600 - 1)), 20_240_229);
+    }
+
+    #[test]
+    fn a_frozen_clock_gives_the_same_board() {
+        let morning = daily_board(at(LEAP_DAY_NOON - 6 * 3600));
+        let evening = daily_board(at(LEAP_DAY_NOON + 6 * 3600));
+        let next_day = daily_board(at(LEAP_DAY_NOON + 24 * 3600));
+
+        assert_eq!(board_code(at(LEAP_DAY_NOON)).seed, 20_240_229);
+        assert_eq!(morning.board, evening.board);
+        assert_ne!(morning.board, next_day.board);
+        assert_eq!(morning.board.mine_count(), 40);
+    }
+}
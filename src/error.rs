@@ -0,0 +1,50 @@
+use std::{fmt, io};
+
+pub enum Error {
+    Io(io::Error),
+    Parse(String),
+    Config(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "IO error: {}", error),
+            Error::Parse(message) => write!(f, "Parse error: {}", message),
+            Error::Config(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+// `main` prints returned errors with their `Debug` representation, so keep it as
+// readable as the `Display` one.
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        if error.is_io() {
+            Error::Io(error.into())
+        } else {
+            Error::Parse(error.to_string())
+        }
+    }
+}
@@ -0,0 +1,706 @@
+use std::{
+    cmp::Ordering,
+    collections::VecDeque,
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    solver::{self, Uncertainty},
+    Board, BoardSnapshot, Coord, Error, OpenResult, Tile,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GameStatus {
+    Playing,
+    Won,
+    Lost,
+}
+
+/// The turns of a two player game, in which the players take turns opening tiles. A
+/// move that hits a mine loses the game for the player who made it, clearing the board
+/// wins it for the player who opened more tiles.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Versus {
+    /// The player on turn, 0 or 1.
+    pub current_player: usize,
+    /// The safe tiles opened by each player.
+    pub opened: [usize; 2],
+}
+
+impl Versus {
+    /// The player who won the finished game, or `None` for a draw and a running game.
+    pub fn winner(&self, status: GameStatus) -> Option<usize> {
+        match status {
+            GameStatus::Playing => None,
+            GameStatus::Lost => Some(1 - self.current_player),
+            GameStatus::Won => match self.opened[0].cmp(&self.opened[1]) {
+                Ordering::Greater => Some(0),
+                Ordering::Less => Some(1),
+                Ordering::Equal => None,
+            },
+        }
+    }
+}
+
+/// A single game: the board, its status and the bookkeeping around it.
+#[derive(Serialize, Deserialize)]
+pub struct GameState {
+    pub mine_count: usize,
+    pub board: Board,
+    pub status: GameStatus,
+    pub hints_used: usize,
+    #[serde(default)]
+    pub assists_used: usize,
+    /// The authored board of a puzzle, which a restart returns to.
+    #[serde(default)]
+    puzzle: Option<Board>,
+    /// Flags, opens and chords that changed the board.
+    #[serde(default)]
+    pub clicks: usize,
+    /// Hitting a mine with more than one life left flags the mine and costs a life
+    /// instead of ending the game.
+    #[serde(default = "default_lives")]
+    pub lives: usize,
+    #[serde(default = "default_lives")]
+    max_lives: usize,
+    #[serde(default)]
+    versus: Option<Versus>,
+    /// The most tiles a single flood fill opens, see [`GameState::set_reveal_budget`].
+    #[serde(default)]
+    reveal_budget: Option<usize>,
+    /// The tiles a flood fill that ran out of its budget still has to visit.
+    #[serde(default)]
+    pending_reveal: VecDeque<usize>,
+    elapsed_seconds: u64,
+    #[serde(skip)]
+    start_time: Option<Instant>,
+    /// Every move that changed the board, see [`HistoryEntry`].
+    #[serde(skip)]
+    history: Vec<HistoryEntry>,
+    /// The board and counters before the current move, compared against in
+    /// [`GameState::finish_move`].
+    #[serde(skip)]
+    move_start: Option<(BoardSnapshot, HistoryEntry)>,
+}
+
+/// A move in the undo history: the changed tiles and the counters before the move.
+#[derive(Clone, Debug)]
+struct HistoryEntry {
+    tiles: Vec<(usize, Tile)>,
+    status: GameStatus,
+    clicks: usize,
+    lives: usize,
+    versus: Option<Versus>,
+}
+
+impl GameState {
+    pub fn new(row_count: usize, col_count: usize, mine_count: usize) -> Self {
+        GameState {
+            mine_count,
+            board: Board::new(row_count, col_count),
+            status: GameStatus::Playing,
+            hints_used: 0,
+            assists_used: 0,
+            puzzle: None,
+            clicks: 0,
+            lives: 1,
+            max_lives: 1,
+            versus: None,
+            reveal_budget: None,
+            pending_reveal: VecDeque::new(),
+            elapsed_seconds: 0,
+            start_time: None,
+            history: Vec::new(),
+            move_start: None,
+        }
+    }
+
+    /// Starts a game on a fixed board with its mines already placed, so the first open
+    /// reveals the authored layout instead of generating mines.
+    pub fn from_puzzle(board: Board) -> Self {
+        let mut state = GameState::new(board.row_count(), board.col_count(), board.mine_count());
+        state.board = board.clone();
+        state.puzzle = Some(board);
+        state
+    }
+
+    /// Reads a puzzle in the text format of [`Board::from_str`](std::str::FromStr).
+    pub fn load_puzzle(path: &str) -> Result<Self, Error> {
+        let board: Board = fs::read_to_string(path)?.parse()?;
+        let tile_count = board.tiles().len();
+
+        if board.mine_count() == 0 || board.mine_count() >= tile_count {
+            return Err(Error::Parse(format!(
+                "The puzzle has {} mines on {} tiles, it needs at least one mine and one free tile",
+                board.mine_count(),
+                tile_count
+            )));
+        }
+
+        for (idx, tile) in board.tiles().iter().enumerate() {
+            let Tile::Open(count) = *tile else {
+                continue;
+            };
+
+            let mine_count = board.count_neighbouring_mines(idx);
+
+            if board.is_mine(idx) || count != mine_count {
+                let coord = Coord::from_index(idx, board.col_count());
+                return Err(Error::Parse(format!(
+                    "The open tile ({}, {}) shows {} but has {} neighbouring mines",
+                    coord.row, coord.col, count, mine_count
+                )));
+            }
+        }
+
+        Ok(GameState::from_puzzle(board))
+    }
+
+    /// Starts a fresh game with the same dimensions and mine count, or the same board
+    /// for a puzzle.
+    pub fn restart(&self) -> Self {
+        let mut state = match &self.puzzle {
+            Some(puzzle) => GameState::from_puzzle(puzzle.clone()),
+            None => {
+                let mut state = GameState::new(
+                    self.board.row_count(),
+                    self.board.col_count(),
+                    self.mine_count,
+                );
+                state.board.set_wrap(self.board.wraps());
+                state.board.set_connectivity(self.board.connectivity());
+                state
+            }
+        };
+
+        state.set_lives(self.max_lives);
+        if self.versus.is_some() {
+            state.set_versus();
+        }
+        state.set_reveal_budget(self.reveal_budget);
+        state
+    }
+
+    /// Limits every move to open at most `budget` tiles, a larger flood fill pauses and
+    /// is carried on by [`GameState::continue_reveal`]. `None` opens the whole flood at
+    /// once.
+    pub fn set_reveal_budget(&mut self, budget: Option<usize>) {
+        self.reveal_budget = budget;
+    }
+
+    /// Whether a flood fill paused because it ran out of its reveal budget.
+    pub fn has_pending_reveal(&self) -> bool {
+        !self.pending_reveal.is_empty()
+    }
+
+    /// Turns the game into a two player game, see [`Versus`].
+    pub fn set_versus(&mut self) {
+        self.versus = Some(Versus::default());
+    }
+
+    pub fn versus(&self) -> Option<&Versus> {
+        self.versus.as_ref()
+    }
+
+    /// Sets the number of lives, which a restart starts with again.
+    pub fn set_lives(&mut self, lives: usize) {
+        self.lives = lives.max(1);
+        self.max_lives = self.lives;
+    }
+
+    /// The number of lives the game started with.
+    pub fn max_lives(&self) -> usize {
+        self.max_lives
+    }
+
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        let mut state: GameState = serde_json::from_reader(BufReader::new(file))?;
+        state.board.update_mine_counts();
+
+        if state.board.has_mines() {
+            state.start_timer();
+        }
+
+        Ok(state)
+    }
+
+    /// A copy of the game without its undo history, e.g. to hand it to another thread.
+    pub fn snapshot(&self) -> Self {
+        GameState {
+            mine_count: self.mine_count,
+            board: self.board.clone(),
+            status: self.status,
+            hints_used: self.hints_used,
+            assists_used: self.assists_used,
+            puzzle: self.puzzle.clone(),
+            clicks: self.clicks,
+            lives: self.lives,
+            max_lives: self.max_lives,
+            versus: self.versus.clone(),
+            reveal_budget: self.reveal_budget,
+            pending_reveal: self.pending_reveal.clone(),
+            elapsed_seconds: self.elapsed_seconds,
+            start_time: self.start_time,
+            history: Vec::new(),
+            move_start: None,
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let mut state = self.snapshot();
+        state.elapsed_seconds = self.elapsed_seconds();
+        state.start_time = None;
+
+        let file = File::create(path)?;
+        serde_json::to_writer(BufWriter::new(file), &state)?;
+
+        Ok(())
+    }
+
+    pub fn start_timer(&mut self) {
+        self.start_time = Some(Instant::now());
+    }
+
+    pub fn elapsed_seconds(&self) -> u64 {
+        self.elapsed_seconds
+            + self
+                .start_time
+                .map_or(0, |start_time| start_time.elapsed().as_secs())
+    }
+
+    pub fn flag(&mut self, tile_idx: usize, question_marks: bool) {
+        self.start_move();
+        self.board.flag(tile_idx, question_marks);
+        log::debug!("Flag tile {}: {:?}", tile_idx, self.board.tiles()[tile_idx]);
+        self.finish_move();
+    }
+
+    pub fn open(&mut self, tile_idx: usize) {
+        self.open_in_waves(tile_idx, |_| {});
+    }
+
+    /// Like [`GameState::open`], but calls `on_wave` after every wave of the flood fill,
+    /// see [`Board::reveal_in_waves`].
+    pub fn open_in_waves(&mut self, tile_idx: usize, on_wave: impl FnMut(&Board)) {
+        if self.start_time.is_none() {
+            self.start_timer();
+        }
+
+        self.start_move();
+        let moves = self.history.len();
+        let open_count = self.board.count_open();
+
+        if self.lives > 1
+            && self.board.is_mine(tile_idx)
+            && self.board.tiles()[tile_idx] != Tile::Flagged
+        {
+            self.lose_life(&[tile_idx]);
+            self.finish_move();
+            self.end_turn(moves, open_count);
+            return;
+        }
+
+        let result = if self.reveal_budget.is_some()
+            && !self.board.is_mine(tile_idx)
+            && self.board.tiles()[tile_idx] != Tile::Flagged
+        {
+            self.reveal_tiles([tile_idx]);
+            OpenResult::Revealed
+        } else {
+            self.board.open_in_waves(tile_idx, on_wave)
+        };
+        log::debug!(
+            "Open tile {} ({:?}), {} tiles revealed",
+            tile_idx,
+            result,
+            self.board.count_open() - open_count
+        );
+        self.update_status(result);
+        self.end_turn(moves, open_count);
+    }
+
+    pub fn chord(&mut self, tile_idx: usize) {
+        self.start_move();
+        let moves = self.history.len();
+        let open_count = self.board.count_open();
+
+        let tiles_to_open = self.board.chord_indices(tile_idx);
+        let (triggered_mines, safe_tiles): (Vec<_>, Vec<_>) = tiles_to_open
+            .into_iter()
+            .partition(|&idx| self.board.is_mine(idx));
+
+        if self.lives > 1 && !triggered_mines.is_empty() {
+            self.lose_life(&triggered_mines);
+            self.reveal_tiles(safe_tiles);
+            self.update_status(OpenResult::Revealed);
+            self.end_turn(moves, open_count);
+            return;
+        }
+
+        let result = if self.reveal_budget.is_some() && triggered_mines.is_empty() {
+            self.reveal_tiles(safe_tiles);
+            OpenResult::Revealed
+        } else {
+            self.board.chord(tile_idx)
+        };
+        log::debug!(
+            "Chord tile {} ({:?}), {} tiles revealed",
+            tile_idx,
+            result,
+            self.board.count_open() - open_count
+        );
+        self.update_status(result);
+        self.end_turn(moves, open_count);
+    }
+
+    /// Opens the next tiles of a flood fill that ran out of its reveal budget. Returns
+    /// `false` if there is no such flood fill.
+    pub fn continue_reveal(&mut self) -> bool {
+        if self.pending_reveal.is_empty() {
+            return false;
+        }
+
+        let clicks = self.clicks;
+        let moves = self.history.len();
+        let open_count = self.board.count_open();
+
+        self.start_move();
+        self.reveal_tiles([]);
+        self.update_status(OpenResult::Revealed);
+        // The continuation belongs to the click that started the flood fill.
+        self.clicks = clicks;
+        self.end_turn(moves, open_count);
+        true
+    }
+
+    /// Reverts the last flag, open or chord. Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(entry) = self.history.pop() else {
+            return false;
+        };
+        for (idx, tile) in entry.tiles {
+            self.board.restore_tile(idx, tile);
+        }
+        self.status = entry.status;
+        self.clicks = entry.clicks;
+        self.lives = entry.lives;
+        self.versus = entry.versus;
+
+        // The paused flood fill belongs to the undone board, so it is rebuilt from the
+        // open zeros that still border concealed tiles.
+        self.pending_reveal.clear();
+        if self.reveal_budget.is_some() {
+            for (idx, tile) in self.board.tiles().iter().enumerate() {
+                if *tile != Tile::Open(0) {
+                    continue;
+                }
+
+                self.board.for_each_neighbour(idx, |neighbour| {
+                    if matches!(
+                        self.board.tiles()[neighbour],
+                        Tile::Concealed | Tile::Question
+                    ) {
+                        self.pending_reveal.push_back(neighbour);
+                    }
+                });
+            }
+        }
+
+        true
+    }
+
+    /// Flags every tile that is certainly a mine, see [`solver::certain_mines`]. Returns
+    /// the number of placed flags.
+    pub fn autoflag(&mut self) -> Result<usize, Uncertainty> {
+        let mines = solver::certain_mines(&self.board, self.mine_count)?;
+
+        self.start_move();
+        for &idx in &mines {
+            self.board.place_flag(idx);
+        }
+        self.finish_move();
+
+        Ok(mines.len())
+    }
+
+    /// Opens every tile that is certainly safe and flags every tile that is certainly a
+    /// mine, see [`solver::certain_deductions`]. Returns the number of opened tiles and
+    /// placed flags.
+    pub fn assist(&mut self) -> (usize, usize) {
+        self.assists_used += 1;
+        let deductions = solver::certain_deductions(&self.board);
+
+        self.start_move();
+        let moves = self.history.len();
+        let open_count = self.board.count_open();
+        let flag_count = self.board.count_flags();
+
+        for &idx in &deductions.mines {
+            self.board.place_flag(idx);
+        }
+        self.reveal_tiles(deductions.safe);
+        self.update_status(OpenResult::Revealed);
+        self.end_turn(moves, open_count);
+
+        (
+            self.board.count_open() - open_count,
+            self.board.count_flags().saturating_sub(flag_count),
+        )
+    }
+
+    pub fn hint(&mut self) -> Option<usize> {
+        self.hints_used += 1;
+        self.board.find_safe_tile()
+    }
+
+    /// Suggests the tile least likely to be a mine together with its estimated chance,
+    /// see [`solver::suggest_guess`].
+    pub fn guess(&mut self) -> Option<(usize, f64)> {
+        self.hints_used += 1;
+        solver::suggest_guess(&self.board)
+    }
+
+    /// The estimated chance of every unknown tile being a mine, see
+    /// [`solver::mine_chances`]. Unlike a hint, it does not count as help.
+    pub fn mine_chances(&self) -> Vec<(usize, f64)> {
+        solver::mine_chances(&self.board)
+    }
+
+    fn update_status(&mut self, result: OpenResult) {
+        self.finish_move();
+
+        if result == OpenResult::HitMine {
+            self.status = GameStatus::Lost;
+        } else if self.board.is_won() {
+            self.status = GameStatus::Won;
+        }
+
+        if self.status != GameStatus::Playing {
+            log::info!("Game over: {:?} after {} clicks", self.status, self.clicks);
+        }
+    }
+
+    /// Flags the triggered mines instead of ending the game, at the cost of a life.
+    fn lose_life(&mut self, triggered_mines: &[usize]) {
+        self.lives -= 1;
+        for &idx in triggered_mines {
+            self.board.place_flag(idx);
+        }
+
+        log::info!(
+            "Hit {} mines, {} lives left",
+            triggered_mines.len(),
+            self.lives
+        );
+    }
+
+    /// Queues the tiles for the flood fill and opens as many as the reveal budget allows.
+    /// The tiles go first, a paused flood fill carries on after them.
+    fn reveal_tiles(&mut self, tiles: impl IntoIterator<Item = usize>) {
+        for idx in tiles {
+            self.pending_reveal.push_front(idx);
+        }
+        let budget = self.reveal_budget.unwrap_or(usize::MAX);
+        self.board.reveal_limited(&mut self.pending_reveal, budget);
+    }
+
+    /// Credits the tiles opened by the move to the player on turn and passes the turn to
+    /// the other player, unless the move changed nothing or ended the game.
+    fn end_turn(&mut self, moves: usize, open_count: usize) {
+        let Some(versus) = &mut self.versus else {
+            return;
+        };
+
+        if self.history.len() == moves {
+            return;
+        }
+
+        versus.opened[versus.current_player] += self.board.count_open() - open_count;
+        if self.status == GameStatus::Playing {
+            versus.current_player = 1 - versus.current_player;
+        }
+    }
+
+    fn start_move(&mut self) {
+        let entry = HistoryEntry {
+            tiles: Vec::new(),
+            status: self.status,
+            clicks: self.clicks,
+            lives: self.lives,
+            versus: self.versus.clone(),
+        };
+        self.move_start = Some((self.board.snapshot(), entry));
+    }
+
+    /// Records the tiles changed since [`GameState::start_move`] as a move, a move that
+    /// changed nothing is not counted.
+    fn finish_move(&mut self) {
+        let Some((board, mut entry)) = self.move_start.take() else {
+            return;
+        };
+        entry.tiles = self.board.changes_since(&board);
+
+        if !entry.tiles.is_empty() {
+            self.history.push(entry);
+            self.clicks += 1;
+        }
+    }
+}
+
+fn default_lives() -> usize {
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn puzzle(board: &str) -> GameState {
+        GameState::from_puzzle(board.parse().unwrap())
+    }
+
+    #[test]
+    fn hitting_a_mine_costs_a_life() {
+        let mut state = puzzle("*##\n###\n##*");
+        state.set_lives(2);
+
+        state.open(0);
+        assert_eq!(state.status, GameStatus::Playing);
+        assert_eq!(state.lives, 1);
+        assert_eq!(state.board.tiles()[0], Tile::Flagged);
+
+        state.open(4);
+        assert_eq!(state.status, GameStatus::Playing);
+
+        state.open(8);
+        assert_eq!(state.status, GameStatus::Lost);
+        assert_eq!(state.lives, 1);
+        assert_eq!(state.board.tiles()[8], Tile::TriggeredMine);
+    }
+
+    #[test]
+    fn a_chord_into_a_mine_costs_a_life() {
+        let mut state = puzzle("*##\n###\n#*#");
+        state.set_lives(3);
+        state.open(2);
+        state.flag(7, false);
+        // The flag sits on a safe tile, so the chord opens the mine at the top left.
+        state.flag(3, false);
+
+        state.chord(4);
+
+        assert_eq!(state.status, GameStatus::Playing);
+        assert_eq!(state.lives, 2);
+        assert_eq!(state.board.tiles()[0], Tile::Flagged);
+        assert_eq!(state.board.tiles()[6], Tile::Open(1));
+    }
+
+    #[test]
+    fn the_puzzle_clock_starts_on_the_first_open() {
+        let mut state = puzzle("*##\n###\n##*");
+        assert!(state.start_time.is_none());
+
+        state.flag(0, false);
+        assert!(state.start_time.is_none());
+
+        state.open(4);
+        assert!(state.start_time.is_some());
+    }
+
+    #[test]
+    fn a_restart_gives_back_the_lives() {
+        let mut state = puzzle("*##\n###\n###");
+        state.set_lives(2);
+        state.open(0);
+        assert_eq!(state.lives, 1);
+
+        let state = state.restart();
+        assert_eq!(state.lives, 2);
+        assert_eq!(state.max_lives(), 2);
+    }
+
+    #[test]
+    fn undo_conceals_a_whole_flood_fill() {
+        let mut state = puzzle(
+            "########\n\
+             ########\n\
+             ########\n\
+             ########\n\
+             ########\n\
+             ########\n\
+             ######*#\n\
+             ########",
+        );
+        state.flag(7, false);
+        let before = state.board.clone();
+
+        state.open(0);
+        assert!(state.board.count_open() > 50);
+
+        assert!(state.undo());
+        assert_eq!(state.board, before);
+        assert_eq!(state.board.count_open(), 0);
+        assert_eq!(state.board.tiles()[7], Tile::Flagged);
+        assert_eq!(state.status, GameStatus::Playing);
+    }
+
+    #[test]
+    fn undo_gives_back_a_lost_life() {
+        let mut state = puzzle("*##\n###\n##*");
+        state.set_lives(2);
+        state.open(1);
+        let clicks = state.clicks;
+
+        state.open(0);
+        assert_eq!(state.lives, 1);
+        assert_eq!(state.board.tiles()[0], Tile::Flagged);
+
+        assert!(state.undo());
+        assert_eq!(state.lives, 2);
+        assert_eq!(state.clicks, clicks);
+        assert_eq!(state.board.tiles()[0], Tile::Concealed);
+    }
+
+    #[test]
+    fn undo_takes_back_the_turn_in_versus() {
+        let mut state = puzzle("*##\n###\n##*");
+        state.set_versus();
+        state.open(1);
+        let versus = state.versus().unwrap().clone();
+
+        state.open(5);
+        assert_ne!(
+            state.versus().unwrap().current_player,
+            versus.current_player
+        );
+
+        assert!(state.undo());
+        assert_eq!(
+            state.versus().unwrap().current_player,
+            versus.current_player
+        );
+        assert_eq!(state.versus().unwrap().opened, versus.opened);
+    }
+
+    #[test]
+    fn a_continuation_is_a_turn_but_not_a_click() {
+        let mut state = puzzle("*#####\n######\n######");
+        state.set_versus();
+        state.set_reveal_budget(Some(2));
+        state.open(17);
+        let clicks = state.clicks;
+        let open_count = state.board.count_open();
+        let player = state.versus().unwrap().current_player;
+
+        assert!(state.continue_reveal());
+        assert_eq!(state.clicks, clicks);
+        let versus = state.versus().unwrap();
+        assert_ne!(versus.current_player, player);
+        assert_eq!(versus.opened[player], state.board.count_open() - open_count);
+    }
+}
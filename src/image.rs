@@ -0,0 +1,107 @@
+use std::{fs, io::Write};
+
+use cli_minesweeper::{Board, Error, Tile};
+
+use crate::{config::NumberColors, tile_color};
+
+const CELL_SIZE: usize = 16;
+const INSET: usize = 5;
+
+const GRID: [u8; 3] = [96, 96, 96];
+const CONCEALED: [u8; 3] = [176, 176, 176];
+const OPENED: [u8; 3] = [224, 224, 224];
+const TRIGGERED: [u8; 3] = [255, 0, 0];
+
+/// Writes the board as a binary PPM image: one cell per tile, separated by grid lines.
+/// Numbers, flags and mines are drawn as a colored square in the middle of their cell,
+/// in the colors of the terminal output.
+pub fn export_ppm(board: &Board, number_colors: &NumberColors, path: &str) -> Result<(), Error> {
+    let width = board.col_count() * (CELL_SIZE + 1) + 1;
+    let height = board.row_count() * (CELL_SIZE + 1) + 1;
+    let mut pixels = vec![GRID; width * height];
+
+    for (idx, tile) in board.tiles().iter().enumerate() {
+        let (background, inset) = tile_colors(tile, number_colors);
+        let left = idx % board.col_count() * (CELL_SIZE + 1) + 1;
+        let top = idx / board.col_count() * (CELL_SIZE + 1) + 1;
+
+        for y in 0..CELL_SIZE {
+            for x in 0..CELL_SIZE {
+                let is_inset = (INSET..CELL_SIZE - INSET).contains(&x)
+                    && (INSET..CELL_SIZE - INSET).contains(&y);
+
+                pixels[(top + y) * width + left + x] = match inset {
+                    Some(color) if is_inset => color,
+                    _ => background,
+                };
+            }
+        }
+    }
+
+    let mut image = Vec::with_capacity(20 + pixels.len() * 3);
+    write!(image, "P6\n{} {}\n255\n", width, height)?;
+    image.extend(pixels.iter().flatten());
+
+    fs::write(path, image)?;
+    Ok(())
+}
+
+fn tile_colors(tile: &Tile, number_colors: &NumberColors) -> ([u8; 3], Option<[u8; 3]>) {
+    let inset = tile_color(tile, number_colors).map(ansi_rgb);
+
+    match tile {
+        Tile::Concealed => (CONCEALED, None),
+        Tile::Question => (CONCEALED, Some(OPENED)),
+        Tile::Flagged => (CONCEALED, inset),
+        Tile::Open(_) | Tile::WrongFlag => (OPENED, inset),
+        Tile::Mine => (OPENED, Some([0, 0, 0])),
+        Tile::TriggeredMine => (TRIGGERED, Some([0, 0, 0])),
+    }
+}
+
+/// The usual RGB value of an ANSI color code used by the terminal output.
+fn ansi_rgb(code: &str) -> [u8; 3] {
+    match code.rsplit(';').next().unwrap_or(code) {
+        "31" => [170, 0, 0],
+        "32" => [0, 128, 0],
+        "33" => [170, 120, 0],
+        "34" => [0, 0, 170],
+        "35" => [170, 0, 170],
+        "36" => [0, 128, 128],
+        "37" | "97" => [255, 255, 255],
+        "90" => [128, 128, 128],
+        "91" => [255, 0, 0],
+        "92" => [0, 220, 0],
+        "93" => [230, 220, 0],
+        "94" => [0, 0, 255],
+        "95" => [255, 0, 255],
+        "96" => [0, 220, 220],
+        _ => [0, 0, 0],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_file::TempFile;
+
+    #[test]
+    fn ppm_has_a_header_and_a_pixel_per_point() {
+        let mut board: Board = "*##\n###".parse().unwrap();
+        board.open(5);
+        let file = TempFile::new("ppm");
+
+        export_ppm(&board, &NumberColors::default(), file.path()).unwrap();
+        let image = fs::read(file.path()).unwrap();
+
+        let (width, height) = (3 * (CELL_SIZE + 1) + 1, 2 * (CELL_SIZE + 1) + 1);
+        let header = format!("P6\n{} {}\n255\n", width, height);
+        assert!(image.starts_with(header.as_bytes()));
+        assert_eq!(image.len() - header.len(), width * height * 3);
+
+        // The top left pixel is on the grid, the one after it in the concealed mine.
+        let pixels = &image[header.len()..];
+        assert_eq!(pixels[..3], GRID);
+        assert_eq!(pixels[(width + 1) * 3..(width + 2) * 3], CONCEALED);
+    }
+}
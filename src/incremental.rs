@@ -0,0 +1,98 @@
+use std::io::{self, Write};
+
+use cli_minesweeper::{Board, Coord, Tile};
+
+use crate::{cell_width, column_labels, print_board, render_cell, row_label_width, RenderOptions};
+
+/// Draws the board in place: the first frame is printed in full, after that only the
+/// tiles that changed since the last frame are rewritten using ANSI cursor positioning.
+#[derive(Debug, Default)]
+pub struct IncrementalRenderer {
+    last_frame: Option<Frame>,
+}
+
+#[derive(Debug)]
+struct Frame {
+    tiles: Vec<Tile>,
+    row_count: usize,
+    col_count: usize,
+    cell_width: usize,
+    highlight: Option<usize>,
+}
+
+impl IncrementalRenderer {
+    /// Forgets the last frame, so the next draw starts from a cleared screen.
+    pub fn reset(&mut self) {
+        self.last_frame = None;
+    }
+
+    /// Draws the board and leaves the cursor on the line below it, with the rest of the
+    /// screen cleared for the status and menu.
+    pub fn draw(
+        &mut self,
+        writer: &mut impl Write,
+        board: &Board,
+        options: &RenderOptions,
+        highlight: Option<usize>,
+    ) -> io::Result<()> {
+        let cell_width = cell_width(board, options, &column_labels(board, options));
+
+        let frame = Frame {
+            tiles: board.tiles().to_vec(),
+            row_count: board.row_count(),
+            col_count: board.col_count(),
+            cell_width,
+            highlight,
+        };
+
+        match &self.last_frame {
+            Some(last_frame)
+                if last_frame.row_count == frame.row_count
+                    && last_frame.col_count == frame.col_count
+                    && last_frame.cell_width == frame.cell_width =>
+            {
+                self.draw_changes(writer, last_frame, &frame, board, options)?;
+            }
+            _ => {
+                write!(writer, "\x1b[H\x1b[2J")?;
+                print_board(writer, board, options, highlight)?;
+            }
+        }
+
+        self.last_frame = Some(frame);
+        writer.flush()
+    }
+
+    fn draw_changes(
+        &self,
+        writer: &mut impl Write,
+        last_frame: &Frame,
+        frame: &Frame,
+        board: &Board,
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let column_width = frame.cell_width + options.separator.chars().count();
+        let first_column = row_label_width(board) + 2;
+
+        for (idx, tile) in frame.tiles.iter().enumerate() {
+            let is_highlighted = frame.highlight == Some(idx);
+            let was_highlighted = last_frame.highlight == Some(idx);
+
+            if *tile == last_frame.tiles[idx] && is_highlighted == was_highlighted {
+                continue;
+            }
+
+            // The header takes the first line, the terminal counts from 1.
+            let coord = Coord::from_index(idx, frame.col_count);
+            write!(
+                writer,
+                "\x1b[{};{}H{}",
+                coord.row + 2,
+                first_column + coord.col * column_width,
+                render_cell(tile, is_highlighted, options, frame.cell_width)
+            )?;
+        }
+
+        write!(writer, "\x1b[{};1H\x1b[J", frame.row_count + 2)
+    }
+}
@@ -0,0 +1,83 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader},
+};
+
+/// A source of input lines for the game, so it can be driven by something other than
+/// the terminal.
+pub trait InputSource {
+    /// Reads the next line including its line ending. Returns an empty string once the
+    /// input is exhausted.
+    fn read_line(&mut self) -> io::Result<String>;
+}
+
+impl<S: InputSource + ?Sized> InputSource for Box<S> {
+    fn read_line(&mut self) -> io::Result<String> {
+        (**self).read_line()
+    }
+}
+
+impl InputSource for io::Stdin {
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut line = String::new();
+        io::Stdin::read_line(self, &mut line)?;
+
+        Ok(line)
+    }
+}
+
+/// Reads the commands of a script file. Blank lines and lines starting with `#` are
+/// skipped, so a script can be spaced out and commented.
+#[derive(Debug)]
+pub struct ScriptInput {
+    reader: BufReader<File>,
+}
+
+impl ScriptInput {
+    pub fn open(path: &str) -> io::Result<Self> {
+        Ok(ScriptInput {
+            reader: BufReader::new(File::open(path)?),
+        })
+    }
+}
+
+impl InputSource for ScriptInput {
+    fn read_line(&mut self) -> io::Result<String> {
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(line);
+            }
+
+            let command = line.trim();
+            if !command.is_empty() && !command.starts_with('#') {
+                return Ok(line);
+            }
+        }
+    }
+}
+
+/// Feeds a fixed list of lines, e.g. a scripted sequence of commands in tests.
+#[derive(Clone, Debug, Default)]
+pub struct StringInput {
+    lines: VecDeque<String>,
+}
+
+impl StringInput {
+    pub fn new<S: Into<String>>(lines: impl IntoIterator<Item = S>) -> Self {
+        StringInput {
+            lines: lines.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl InputSource for StringInput {
+    fn read_line(&mut self) -> io::Result<String> {
+        Ok(self
+            .lines
+            .pop_front()
+            .map(|line| line + "\n")
+            .unwrap_or_default())
+    }
+}
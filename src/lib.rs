@@ -0,0 +1,25 @@
+//! The minesweeper engine behind the `cli-minesweeper` terminal game.
+//!
+//! Features:
+//! - `cli` (default): builds the terminal game binary.
+//! - `wasm`: adds the [`wasm::Game`] wrapper for web front-ends. The engine itself takes
+//!   its random number generators as parameters, so it builds for
+//!   `wasm32-unknown-unknown` with
+//!   `cargo build --lib --target wasm32-unknown-unknown --no-default-features --features wasm`.
+
+mod board;
+mod error;
+mod game;
+mod input;
+pub mod solver;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use board::{
+    coord_to_index, count_neighbouring_mines, for_each_neighbour, for_each_wrapping_neighbour,
+    generate_mine_positions, get_neighbouring_indices, get_wrapping_neighbouring_indices, Board,
+    BoardSnapshot, Connectivity, Coord, NeighbourSummary, OpenResult, Tile,
+};
+pub use error::Error;
+pub use game::{GameState, GameStatus, Versus};
+pub use input::{InputSource, ScriptInput, StringInput};
@@ -0,0 +1,46 @@
+use std::{fs::File, io::Write, sync::Mutex, time::Instant};
+
+use cli_minesweeper::Error;
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Writes every log record to a file, prefixed with the seconds since the start.
+struct FileLogger {
+    file: Mutex<File>,
+    start_time: Instant,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(
+                file,
+                "{:>9.3} {:<5} {}",
+                self.start_time.elapsed().as_secs_f64(),
+                record.level(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// Sends the game events logged by the engine and the game to the file.
+pub fn init(path: &str) -> Result<(), Error> {
+    let logger = FileLogger {
+        file: Mutex::new(File::create(path)?),
+        start_time: Instant::now(),
+    };
+
+    log::set_boxed_logger(Box::new(logger)).map_err(|error| Error::Config(error.to_string()))?;
+    log::set_max_level(LevelFilter::Debug);
+    Ok(())
+}
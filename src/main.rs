@@ -1,232 +1,2361 @@
-use std::{collections::HashSet, io};
+mod analysis;
+#[cfg(feature = "autosave")]
+mod autosave;
+mod braille;
+mod config;
+mod daily;
+mod image;
+mod incremental;
+mod logger;
+mod messages;
+mod replay;
+mod scores;
+mod share;
+mod stats;
+#[cfg(test)]
+mod temp_file;
+mod tui;
 
-use rand::{
-    seq::{IteratorRandom, SliceRandom},
-    Rng,
+use std::{
+    io::{self, Write},
+    thread,
+    time::Duration,
 };
 
-#[derive(Clone, Debug, PartialEq)]
-enum Tile {
-    Flagged,
-    Concealed,
-    Open(usize),
-}
+use analysis::Analysis;
+use cli_minesweeper::{
+    solver::{self, Rating, Uncertainty},
+    Board, Coord, Error, GameState, GameStatus, InputSource, ScriptInput, Tile,
+};
+use config::{Config, CoordStyle, NumberColors, Symbols};
+use incremental::IncrementalRenderer;
+use messages::{fill, Messages};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use replay::{Move, Recorder, ReplaySettings};
+use scores::{HighScores, Score};
+use share::BoardCode;
+use stats::SessionStats;
+
+const NO_GUESS_ATTEMPTS: usize = 1000;
+const EASY_START_ATTEMPTS: usize = 100;
+
+/// The 256-color backgrounds of the heatmap shades, from green for safe to red.
+const HEAT_COLORS: [u8; 10] = [22, 28, 34, 70, 106, 142, 178, 172, 166, 160];
 
 #[derive(Clone, Debug)]
 enum InputAction {
     Flag(usize),
     Open(usize),
+    Chord(usize),
+    Info(usize),
+    Save(String),
+    Snapshot(String),
+    Undo,
+    Hint,
+    Autoflag,
+    Assist,
+    Continue,
+    Guess,
+    Heatmap,
+    Help,
+    Code,
+    Restart,
     Exit,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 enum InputError {
     ParseError,
-    InvalidCoords((usize, usize)),
+    RowOutOfRange {
+        row: usize,
+        row_count: usize,
+    },
+    ColOutOfRange {
+        col: usize,
+        col_count: usize,
+    },
+    IndexOutOfRange {
+        index: usize,
+        tile_count: usize,
+    },
+    InBatch {
+        segment: usize,
+        error: Box<InputError>,
+    },
+    NoPreviousCommand,
+    Io(io::Error),
+}
+
+impl InputError {
+    fn message(&self, messages: &Messages) -> String {
+        match self {
+            InputError::ParseError => messages.parse_error.to_string(),
+            InputError::RowOutOfRange { row, row_count } => {
+                fill(messages.row_out_of_range, &[row, &(row_count - 1)])
+            }
+            InputError::ColOutOfRange { col, col_count } => {
+                fill(messages.col_out_of_range, &[col, &(col_count - 1)])
+            }
+            InputError::IndexOutOfRange { index, tile_count } => {
+                fill(messages.index_out_of_range, &[index, &(tile_count - 1)])
+            }
+            InputError::InBatch { segment, error } => {
+                fill(messages.batch_error, &[segment, &error.message(messages)])
+            }
+            InputError::NoPreviousCommand => messages.no_previous_command.to_string(),
+            InputError::Io(error) => fill(messages.read_error, &[error]),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RenderOptions {
+    use_color: bool,
+    coord_style: CoordStyle,
+    separator: String,
+    zero_char: char,
+    symbols: Symbols,
+    number_colors: NumberColors,
+    messages: &'static Messages,
+    show_progress: bool,
+    clear_screen: bool,
+    accessible: bool,
+    compact: bool,
+}
+
+impl RenderOptions {
+    fn new(config: &Config) -> Self {
+        RenderOptions {
+            use_color: config.color.enabled(),
+            coord_style: config.coords,
+            separator: config.separator.clone(),
+            zero_char: config.zero_char,
+            symbols: config.symbols.clone(),
+            number_colors: config.number_colors,
+            messages: config.language.messages(),
+            show_progress: config.progress,
+            clear_screen: config.clear_screen,
+            accessible: config.accessible,
+            compact: config.compact,
+        }
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let config = config::parse_args(std::env::args().skip(1))
+        .inspect_err(|_| eprintln!("{}\n", config::USAGE))?;
+
+    if let Some(path) = &config.log_path {
+        logger::init(path)?;
+    }
+
+    if config.show_scores {
+        HighScores::load()?.print(config.language.messages());
+        return Ok(());
+    }
+
+    let render_options = RenderOptions::new(&config);
+
+    if config.autoplay {
+        return autoplay(&config, &render_options);
+    }
+
+    if let Some(path) = &config.replay_path {
+        return play_replay(path, &config, &render_options);
+    }
+
+    if let Some(path) = &config.replay_json {
+        return play_replay_json(path, &config, &render_options);
+    }
+
+    let state = match (&config.load_path, &config.puzzle_path) {
+        (Some(path), _) => GameState::load(path)?,
+        (None, Some(path)) => {
+            let mut state = GameState::load_puzzle(path)?;
+            state.set_lives(config.lives);
+            if config.versus {
+                state.set_versus();
+            }
+            state.set_reveal_budget(config.reveal_budget);
+            state
+        }
+        (None, None) => {
+            let mut state = GameState::new(config.row_count, config.col_count, config.mine_count);
+            state.board.set_wrap(config.wrap);
+            state.board.set_connectivity(config.connectivity);
+            state.set_lives(config.lives);
+            if config.versus {
+                state.set_versus();
+            }
+            state.set_reveal_budget(config.reveal_budget);
+            state
+        }
+    };
+
+    if config.tui {
+        let seed = new_seed(&config);
+        log_new_game(&state, seed);
+        return tui::run(
+            state,
+            &config,
+            &render_options,
+            &mut StdRng::seed_from_u64(seed),
+        );
+    }
+
+    #[cfg(feature = "autosave")]
+    let autosave = autosave::Autosave::install(render_options.messages)?;
+    #[cfg(feature = "autosave")]
+    let on_turn = |state: &GameState| autosave.update(state);
+    #[cfg(not(feature = "autosave"))]
+    let on_turn = |_: &GameState| {};
+
+    // A script plays without prompting and ends with the game, printing every turn or,
+    // with `--quiet`, only the final board.
+    let mut input: Box<dyn InputSource> = match &config.script_path {
+        Some(path) => Box::new(ScriptInput::open(path)?),
+        None => Box::new(io::stdin()),
+    };
+
+    play(
+        state,
+        &config,
+        &render_options,
+        &mut input,
+        config.script_path.is_some(),
+        on_turn,
+    )?;
+
+    Ok(())
 }
 
-fn main() {
-    let row_count = 10_usize;
-    let col_count = 5_usize;
-    let mine_count = 1;
+/// Plays on the terminal with the commands read from `input` until the player exits, or
+/// for a script until the game is over. `on_turn` is called with the game before every
+/// command. Returns the last game.
+fn play(
+    mut state: GameState,
+    config: &Config,
+    render_options: &RenderOptions,
+    input: &mut impl InputSource,
+    scripted: bool,
+    mut on_turn: impl FnMut(&GameState),
+) -> Result<GameState, Error> {
+    let messages = render_options.messages;
+    let mut seed = new_seed(config);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut first_click = None;
+    log_new_game(&state, seed);
+    let mut recorder = (config.record_path.is_some() || config.export_json.is_some())
+        .then(|| Recorder::new(ReplaySettings::new(&state, config, seed)));
+
+    let mut stats = SessionStats::default();
+    let mut renderer = IncrementalRenderer::default();
 
-    let mut board = vec![Tile::Concealed; col_count*row_count];
-    let mut mine_indeces = HashSet::new();
+    let mut message = None;
+    let mut highlight = None;
+    let mut heatmap: Option<Vec<(usize, f64)>> = None;
+    let mut last_action = None;
+    let mut loss_explanation = None;
+    let mut show_help = false;
+    let mut last_input = None;
+
+    if let Some(code) = config.code {
+        message = open_code_click(
+            &mut state,
+            code.first_click,
+            seed,
+            config,
+            render_options,
+            &mut rng,
+            &mut recorder,
+        );
+        first_click = Some(code.first_click);
+    }
 
     loop {
-        clearscreen::clear().expect("Failed to print to console");
-        print_board(&board, row_count, col_count);
-        print_menu();
+        match state.status {
+            GameStatus::Playing => {}
+            GameStatus::Won | GameStatus::Lost => {
+                stats.record(&state);
+
+                if let Some(path) = &config.export_image {
+                    if let Err(error) = image::export_ppm(&state.board, &config.number_colors, path)
+                    {
+                        eprintln!("{}", fill(messages.export_failed, &[path, &error]));
+                    }
+                }
+
+                if let (Some(recorder), Some(path)) = (&recorder, &config.record_path) {
+                    if let Err(error) = recorder.save(path) {
+                        eprintln!("{}", fill(messages.replay_save_failed, &[path, &error]));
+                    }
+                }
+
+                if let (Some(recorder), Some(path)) = (&recorder, &config.export_json) {
+                    let analysis = Analysis::new(&state, recorder.recording());
+                    if let Err(error) = analysis.save(path) {
+                        eprintln!("{}", fill(messages.game_export_failed, &[path, &error]));
+                    }
+                }
+
+                if let Some(versus) = state.versus() {
+                    let result = match versus.winner(state.status) {
+                        Some(player) => fill(messages.versus_winner, &[&(player + 1)]),
+                        None => messages.versus_draw.to_string(),
+                    };
+                    print_game_over(&state, render_options, &result)?;
+                    println!(
+                        "{}",
+                        fill(
+                            messages.versus_opened,
+                            &[&versus.opened[0], &versus.opened[1]]
+                        )
+                    );
+                } else if state.status == GameStatus::Won {
+                    print_game_over(&state, render_options, messages.won)?;
+                    if !scripted {
+                        record_score(input, &state, messages)?;
+                    }
+                } else {
+                    let result = match loss_explanation.take() {
+                        Some(explanation) => format!("{}\n{}", messages.lost, explanation),
+                        None => messages.lost.to_string(),
+                    };
+                    print_game_over(&state, render_options, &result)?;
+                }
+
+                if scripted || !request_play_again(input, messages)? {
+                    stats.print(messages);
+                    return Ok(state);
+                }
+
+                state = state.restart();
+                seed = new_seed(config);
+                rng = StdRng::seed_from_u64(seed);
+                first_click = None;
+                loss_explanation = None;
+                renderer.reset();
+                log_new_game(&state, seed);
+                if let Some(recorder) = &mut recorder {
+                    *recorder = Recorder::new(ReplaySettings::new(&state, config, seed));
+                }
+                if let Some(code) = config.code {
+                    message = open_code_click(
+                        &mut state,
+                        code.first_click,
+                        seed,
+                        config,
+                        render_options,
+                        &mut rng,
+                        &mut recorder,
+                    );
+                    first_click = Some(code.first_click);
+                }
+            }
+        }
+
+        if scripted && config.quiet {
+            highlight = None;
+            heatmap = None;
+        } else if let Some(chances) = heatmap.take() {
+            clear_screen(&mut io::stdout().lock(), render_options)?;
+            print_heatmap(
+                &mut io::stdout().lock(),
+                &state.board,
+                &chances,
+                render_options,
+            )?;
+            renderer.reset();
+        } else if config.incremental {
+            renderer.draw(
+                &mut io::stdout().lock(),
+                &state.board,
+                render_options,
+                highlight.take(),
+            )?;
+        } else {
+            clear_screen(&mut io::stdout().lock(), render_options)?;
+            print_board(
+                &mut io::stdout().lock(),
+                &state.board,
+                render_options,
+                highlight.take(),
+            )?;
+        }
+        if !(scripted && config.quiet) {
+            print_status(&mut io::stdout().lock(), &state, render_options)?;
+            if let Some(last_action) = last_action.take() {
+                println!("{}", last_action);
+            }
+
+            if let Some(message) = message.take() {
+                println!("\n{}", message);
+            }
+        }
+
+        if show_help {
+            show_help = false;
+            print_help(&mut io::stdout().lock(), config.coords, messages)?;
+        } else if !scripted {
+            print_menu(&mut io::stdout().lock(), config.coords, messages)?;
+        }
+
+        on_turn(&state);
 
-        let action = match request_input(row_count, col_count) {
+        let actions = match request_input(
+            input,
+            state.board.row_count(),
+            state.board.col_count(),
+            config.coords,
+            &mut last_input,
+        ) {
             Ok(res) => res,
+            Err(InputError::Io(error)) => return Err(error.into()),
+            Err(error) if scripted => {
+                eprintln!("{}", error.message(messages));
+                continue;
+            }
             Err(error) => {
-                print_parser_error(error);
+                // Ring the terminal bell, the error itself is shown once the board is redrawn.
+                message = Some(format!("\x07{}", error.message(messages)));
                 continue;
             }
         };
 
-        match action {
-            InputAction::Exit => return,
-            InputAction::Flag(tile_index) => flag_tile(&mut board, tile_index),
-            InputAction::Open(tile_index) => {
+        for action in actions {
+            // A batch stops at the move that ends the game.
+            if state.status != GameStatus::Playing {
+                break;
+            }
+
+            log::debug!("Action: {:?}", action);
+            let recorded_action = recorder
+                .as_ref()
+                .map(|_| (action.clone(), state.board.snapshot()));
+            let lives = state.lives;
+            let open_count = state.board.count_open();
+            let mut described_action = config.last_action.then(|| action.clone());
+
+            match action {
+                InputAction::Exit => {
+                    if scripted && config.quiet {
+                        print_board(&mut io::stdout().lock(), &state.board, render_options, None)?;
+                        print_status(&mut io::stdout().lock(), &state, render_options)?;
+                    }
+                    stats.print(messages);
+                    return Ok(state);
+                }
+                InputAction::Snapshot(path) => {
+                    message = Some(
+                        match image::export_ppm(&state.board, &config.number_colors, &path) {
+                            Ok(()) => fill(messages.exported, &[&path]),
+                            Err(error) => fill(messages.export_failed, &[&path, &error]),
+                        },
+                    );
+                }
+                InputAction::Save(path) => {
+                    message = Some(match state.save(&path) {
+                        Ok(()) => fill(messages.saved, &[&path]),
+                        Err(error) => fill(messages.save_failed, &[&path, &error]),
+                    });
+                }
+                InputAction::Restart => {
+                    state = state.restart();
+                    seed = new_seed(config);
+                    rng = StdRng::seed_from_u64(seed);
+                    first_click = None;
+                    loss_explanation = None;
+                    renderer.reset();
+                    log_new_game(&state, seed);
+                    if let Some(recorder) = &mut recorder {
+                        *recorder = Recorder::new(ReplaySettings::new(&state, config, seed));
+                    }
+                    if let Some(code) = config.code {
+                        message = open_code_click(
+                            &mut state,
+                            code.first_click,
+                            seed,
+                            config,
+                            render_options,
+                            &mut rng,
+                            &mut recorder,
+                        );
+                        first_click = Some(code.first_click);
+                    }
+                }
+                InputAction::Code => {
+                    message = Some(match first_click {
+                        Some(first_click) => fill(
+                            messages.board_code,
+                            &[&BoardCode::new(&state, config, seed, first_click)],
+                        ),
+                        None if state.board.has_mines() => messages.no_board_code.to_string(),
+                        None => messages.board_code_after_first_click.to_string(),
+                    });
+                }
+                InputAction::Undo if state.versus().is_some() => {
+                    message = Some(messages.undo_in_versus.to_string());
+                    described_action = None;
+                }
+                InputAction::Undo => {
+                    if !state.undo() {
+                        message = Some(messages.nothing_to_undo.to_string());
+                        described_action = None;
+                    }
+                }
+                InputAction::Help => show_help = true,
+                InputAction::Assist => {
+                    let (open_count, flag_count) = state.assist();
+                    message = Some(if open_count == 0 && flag_count == 0 {
+                        messages.assist_nothing.to_string()
+                    } else {
+                        fill(messages.assist, &[&open_count, &flag_count])
+                    });
+                }
+                InputAction::Continue => {
+                    if !state.continue_reveal() {
+                        message = Some(messages.nothing_to_continue.to_string());
+                        described_action = None;
+                    }
+                }
+                InputAction::Autoflag => {
+                    message = Some(match state.autoflag() {
+                        Ok(flag_count) => fill(messages.autoflag_placed, &[&flag_count]),
+                        Err(Uncertainty::Contradiction(tile_index)) => fill(
+                            messages.autoflag_contradiction,
+                            &[&format_coords(
+                                Coord::from_index(tile_index, state.board.col_count()),
+                                config.coords,
+                            )],
+                        ),
+                        Err(Uncertainty::Undecided) => messages.autoflag_undecided.to_string(),
+                    });
+                }
+                InputAction::Hint => {
+                    let col_count = state.board.col_count();
+
+                    message = Some(match state.hint() {
+                        Some(tile_index) => {
+                            highlight = Some(tile_index);
+                            fill(
+                                messages.hint,
+                                &[&format_coords(
+                                    Coord::from_index(tile_index, col_count),
+                                    config.coords,
+                                )],
+                            )
+                        }
+                        None => messages.no_hint.to_string(),
+                    });
+                }
+                InputAction::Guess if !state.board.has_mines() => {
+                    message = Some(messages.guess_without_mines.to_string());
+                }
+                InputAction::Guess => {
+                    let col_count = state.board.col_count();
+
+                    message = Some(match state.guess() {
+                        Some((tile_index, chance)) => {
+                            highlight = Some(tile_index);
+                            fill(
+                                messages.guess,
+                                &[
+                                    &format_coords(
+                                        Coord::from_index(tile_index, col_count),
+                                        config.coords,
+                                    ),
+                                    &format!("{:.0}", chance * 100.0),
+                                ],
+                            )
+                        }
+                        None => messages.no_guess.to_string(),
+                    });
+                }
+                InputAction::Heatmap if !state.board.has_mines() => {
+                    message = Some(messages.guess_without_mines.to_string());
+                }
+                InputAction::Heatmap => {
+                    heatmap = Some(state.mine_chances());
+                    message = Some(messages.heatmap.to_string());
+                }
+                InputAction::Flag(tile_index) => {
+                    if let Some(refusal) = flag_tile(&mut state, tile_index, config) {
+                        append_message(&mut message, refusal);
+                    }
+                }
+                InputAction::Open(tile_index) => {
+                    let is_first_click = !state.board.has_mines();
+                    if !config.no_guess
+                        && state.lives == 1
+                        && state.board.is_mine(tile_index)
+                        && state.board.tiles()[tile_index] != Tile::Flagged
+                    {
+                        loss_explanation = Some(explain_loss(&state.board, render_options));
+                    }
+                    let open_message =
+                        open_tile(&mut state, tile_index, config, &mut rng, |board| {
+                            animate_wave(board, render_options, config.animate)
+                        });
+                    if let Some(open_message) = open_message {
+                        append_message(&mut message, open_message);
+                    }
+                    if config.animate.is_some() {
+                        renderer.reset();
+                    }
+
+                    if is_first_click && state.board.has_mines() {
+                        first_click = Some(tile_index);
 
-                if mine_indeces.capacity() == 0 {
-                    mine_indeces =
-                        generate_mine_positions(mine_count, tile_index, row_count, col_count);
+                        let code = BoardCode::new(&state, config, seed, tile_index);
+                        append_message(&mut message, fill(messages.board_code, &[&code]));
+
+                        if config.head_start > 0 && state.status == GameStatus::Playing {
+                            let opened = open_head_start(
+                                &mut state,
+                                config.head_start,
+                                &mut rng,
+                                &mut recorder,
+                            );
+                            append_message(&mut message, fill(messages.head_start, &[&opened]));
+                        }
+                    }
+                }
+                InputAction::Chord(tile_index) => {
+                    if state.lives == 1 {
+                        loss_explanation =
+                            explain_chord_loss(&state.board, tile_index, render_options);
+                    }
+                    state.chord(tile_index);
                 }
+                InputAction::Info(tile_index) => {
+                    message = Some(tile_info(&state.board, tile_index, render_options));
+                }
+            }
 
-                if mine_indeces.contains(&tile_index) {
-                    println!("You lost!");
-                    return;
+            // A refused move, say an undo in versus mode, is left out of the recording,
+            // since the replay would not refuse it.
+            if let (Some(recorder), Some((action, board))) = (&mut recorder, recorded_action) {
+                if !state.board.changes_since(&board).is_empty() {
+                    recorder.record(&action);
                 }
+            }
+
+            if state.lives < lives {
+                append_message(&mut message, fill(messages.life_lost, &[&state.lives]));
+            }
 
-                reveal_tiles(&mut board, tile_index, &mine_indeces, row_count, col_count);
+            if let Some(action) = described_action {
+                let revealed = state.board.count_open().saturating_sub(open_count);
+                last_action = describe_action(&action, &state.board, revealed, render_options);
             }
         }
     }
 }
 
-fn reveal_tiles(
-    board: &mut [Tile],
-    tile_idx: usize,
-    mine_indeces: &HashSet<usize>,
-    row_count: usize,
-    col_count: usize,
-) {
-    reveal_recoursively(board, mine_indeces, &HashSet::from_iter(vec![tile_idx]), row_count, col_count);
+/// Opens the fixed first click of a board from `--code` or `--daily`, followed by the
+/// head start. Returns a message for the player.
+fn open_code_click(
+    state: &mut GameState,
+    first_click: usize,
+    seed: u64,
+    config: &Config,
+    render_options: &RenderOptions,
+    rng: &mut StdRng,
+    recorder: &mut Option<Recorder>,
+) -> Option<String> {
+    let messages = render_options.messages;
+
+    if let Some(recorder) = recorder {
+        recorder.record(&InputAction::Open(first_click));
+    }
+    let mut message = open_tile(state, first_click, config, rng, |board| {
+        animate_wave(board, render_options, config.animate)
+    });
+
+    if config.head_start > 0 && state.status == GameStatus::Playing {
+        let opened = open_head_start(state, config.head_start, rng, recorder);
+        append_message(&mut message, fill(messages.head_start, &[&opened]));
+    }
+
+    if config.daily {
+        append_message(&mut message, fill(messages.daily, &[&seed]));
+    }
+
+    message
 }
 
-fn reveal_recoursively(
-    board: &mut [Tile],
-    mine_indeces: &HashSet<usize>,
-    tiles_to_reveal: &HashSet<usize>,
-    row_count: usize,
-    col_count: usize,
-) {
-    if tiles_to_reveal.is_empty() {
+/// Draws the board after a wave of the flood fill and waits for the `--animate` delay.
+fn animate_wave(board: &Board, options: &RenderOptions, delay: Option<u64>) {
+    let Some(delay) = delay else {
         return;
+    };
+
+    let mut stdout = io::stdout().lock();
+    let _ = clear_screen(&mut stdout, options)
+        .and_then(|()| print_board(&mut stdout, board, options, None))
+        .and_then(|()| stdout.flush());
+    thread::sleep(Duration::from_millis(delay));
+}
+
+/// Describes a tile and its neighbourhood without revealing anything the board does
+/// not show.
+fn tile_info(board: &Board, tile_index: usize, options: &RenderOptions) -> String {
+    let messages = options.messages;
+    let coords = format_coords(
+        Coord::from_index(tile_index, board.col_count()),
+        options.coord_style,
+    );
+
+    let tile = match board.tiles()[tile_index] {
+        Tile::Concealed => messages.info_concealed.to_string(),
+        Tile::Flagged => messages.info_flagged.to_string(),
+        Tile::Question => messages.info_question.to_string(),
+        Tile::Open(mine_count) => fill(messages.info_open, &[&mine_count]),
+        Tile::Mine | Tile::TriggeredMine => messages.info_mine.to_string(),
+        Tile::WrongFlag => messages.info_wrong_flag.to_string(),
+    };
+
+    let summary = board.neighbour_summary(tile_index);
+
+    fill(
+        messages.info,
+        &[
+            &coords,
+            &tile,
+            &board.neighbours(tile_index).len(),
+            &summary.flagged,
+            &summary.concealed,
+        ],
+    )
+}
+
+/// Explains a loss judged on the board before the mine was opened: either the open
+/// numbers showed a tile that was certainly safe, or every move left was a guess.
+fn explain_loss(board: &Board, options: &RenderOptions) -> String {
+    let messages = options.messages;
+
+    match solver::certain_deductions(board).safe.first() {
+        Some(&tile_index) => fill(
+            messages.loss_safe_move,
+            &[&format_coords(
+                Coord::from_index(tile_index, board.col_count()),
+                options.coord_style,
+            )],
+        ),
+        None => messages.loss_forced_guess.to_string(),
     }
+}
 
-    let mut neighbours_to_reveal = HashSet::new();
-    for &tile_idx in tiles_to_reveal {
+/// Explains a loss by a chord, judged on the board before it: a chord only opens a
+/// mine if one of the flags next to the number is wrong. Returns `None` if the chord
+/// opens no mine.
+fn explain_chord_loss(board: &Board, tile_index: usize, options: &RenderOptions) -> Option<String> {
+    if !board
+        .chord_indices(tile_index)
+        .into_iter()
+        .any(|idx| board.is_mine(idx))
+    {
+        return None;
+    }
 
-        if board[tile_idx] == Tile::Concealed {
-            let mine_count =
-                count_neighbouring_mines(tile_idx, mine_indeces, row_count, col_count);
-            board[tile_idx] = Tile::Open(mine_count);
+    let wrong_flag = board
+        .neighbours(tile_index)
+        .into_iter()
+        .filter(|&idx| board.tiles()[idx] == Tile::Flagged && !board.is_mine(idx))
+        .min()?;
 
-            if mine_count == 0 {
-                neighbours_to_reveal.extend(
-                    get_neighbouring_indices(tile_idx, row_count, col_count)
-                        .into_iter()
-                        .filter(|&idx| board[idx] == Tile::Concealed),
-                );
-            }
+    Some(fill(
+        options.messages.loss_wrong_flag,
+        &[&format_coords(
+            Coord::from_index(wrong_flag, board.col_count()),
+            options.coord_style,
+        )],
+    ))
+}
+
+/// Adds a line to the message shown after the next redraw, keeping the messages of the
+/// earlier commands of a batch.
+fn append_message(message: &mut Option<String>, line: String) {
+    *message = Some(match message.take() {
+        Some(message) => format!("{}\n{}", message, line),
+        None => line,
+    });
+}
+
+/// Flags the tile, returning a message if the flag was refused.
+fn flag_tile(state: &mut GameState, tile_index: usize, config: &Config) -> Option<String> {
+    if config.strict_flags
+        && state.board.tiles()[tile_index] == Tile::Concealed
+        && state.board.count_flags() >= state.mine_count
+    {
+        return Some(config.language.messages().out_of_flags.to_string());
+    }
+
+    state.flag(tile_index, config.question_marks);
+    None
+}
+
+/// Opens the tile, placing the mines first if this is the first click of the game.
+/// Returns a message for the player if something went wrong.
+fn open_tile(
+    state: &mut GameState,
+    tile_index: usize,
+    config: &Config,
+    rng: &mut StdRng,
+    on_wave: impl FnMut(&Board),
+) -> Option<String> {
+    let text = config.language.messages();
+    if state.board.tiles()[tile_index] == Tile::Flagged {
+        return Some(text.tile_flagged.to_string());
+    }
+
+    let mut messages = Vec::new();
+    let is_first_click = !state.board.has_mines();
+
+    if is_first_click {
+        let solvable = place_mines(&mut state.board, state.mine_count, tile_index, config, rng);
+
+        if !solvable {
+            messages.push(text.no_solvable_board.to_string());
         }
+    }
+
+    let rating = (is_first_click && !state.board.is_mine(tile_index))
+        .then(|| rating_text(solver::rate(&state.board, tile_index), text));
+    state.open_in_waves(tile_index, on_wave);
 
-        reveal_recoursively(board, mine_indeces, &neighbours_to_reveal, row_count, col_count);
+    if let (Some(rating), false) = (rating, config.quiet) {
+        if config.no_guess || config.first_click_safe > 1 {
+            messages.push(opening_report(&state.board, tile_index, &rating, text));
+        } else {
+            messages.push(fill(text.rating, &[&rating]));
+        }
     }
+
+    (!messages.is_empty()).then(|| messages.join("\n"))
 }
 
-fn flag_tile(board: &mut [Tile], tile_idx: usize) {
-    board[tile_idx] = match board[tile_idx] {
-        Tile::Flagged => Tile::Concealed,
-        Tile::Concealed => Tile::Flagged,
-        Tile::Open(count) => Tile::Open(count),
+/// Opens up to `count` random safe tiles after the first click for `--head-start`, at
+/// least three tiles apart so that their openings spread over the board. Stops once half
+/// of the safe tiles are open, so the board is not solved for the player. The opened
+/// tiles are recorded as moves, but do not count as clicks. Returns how many tiles were
+/// picked.
+fn open_head_start(
+    state: &mut GameState,
+    count: usize,
+    rng: &mut StdRng,
+    recorder: &mut Option<Recorder>,
+) -> usize {
+    let board = &state.board;
+    let safe_count = board.tiles().len() - state.mine_count;
+    let mut candidates = (0..board.tiles().len())
+        .filter(|&idx| board.tiles()[idx] == Tile::Concealed && !board.is_mine(idx))
+        .collect::<Vec<_>>();
+    candidates.shuffle(rng);
+
+    let clicks = state.clicks;
+    let mut opened = Vec::new();
+    for tile_index in candidates {
+        if opened.len() == count || state.board.count_open() >= safe_count / 2 {
+            break;
+        }
+
+        let too_close = state
+            .board
+            .tiles_within(tile_index, 2)
+            .iter()
+            .any(|idx| opened.contains(idx));
+        if too_close || state.board.tiles()[tile_index] != Tile::Concealed {
+            continue;
+        }
+
+        if let Some(recorder) = recorder {
+            recorder.record(&InputAction::Open(tile_index));
+        }
+        state.open(tile_index);
+        opened.push(tile_index);
     }
+    state.clicks = clicks;
+
+    opened.len()
 }
 
-fn print_board(board: &[Tile], row_count: usize, col_count: usize) {
-    let res = board
-        .chunks(col_count)
-        .map(|row| {
-            row.iter()
-                .map(|tile| match tile {
-                    Tile::Flagged => "F".to_string(),
-                    Tile::Open(count) => count.to_string(),
-                    Tile::Concealed => "#".to_string(),
-                })
-                .collect::<Vec<_>>()
-                .join(" ")
-        })
-        .collect::<Vec<_>>()
-        .join("\n");
+/// Describes the area opened by the first click and how much guessing the rest of the
+/// board needs.
+fn opening_report(board: &Board, first_click: usize, rating: &str, messages: &Messages) -> String {
+    let opened = board
+        .tiles()
+        .iter()
+        .filter(|tile| matches!(tile, Tile::Open(_)))
+        .count();
 
-    println!("{}", res);
+    let opening = match board.tiles()[first_click] {
+        Tile::Open(0) => fill(messages.opening, &[&opened]),
+        _ => messages.opening_single_tile.to_string(),
+    };
+
+    fill(messages.opening_rated, &[&opening, &rating])
 }
 
-fn print_menu() {
-    println!("\n");
-    println!("Type 'x <row> <col>' to open a tile");
-    println!("Type 'f <row> <col>' to set a flag");
-    println!("Type 'exit' to exit");
-    println!("\n");
+fn rating_text(rating: Rating, messages: &Messages) -> String {
+    match rating {
+        Rating::LogicOnly => messages.rating_logic_only.to_string(),
+        Rating::OneGuess => messages.rating_one_guess.to_string(),
+        Rating::Guessy(guesses) => fill(messages.rating_guessy, &[&guesses]),
+    }
 }
 
-fn request_input(row_size: usize, col_size: usize) -> Result<InputAction, InputError> {
-    let mut input = String::new();
-    std::io::stdin().read_line(&mut input).unwrap();
+/// Places the mines around the first click. With `--easy-start` the mines are placed
+/// again until the first click is safe and has no neighbouring mines, keeping the last layout if
+/// none is found. Returns `false` if a board solvable without guessing was requested
+/// but not found.
+fn place_mines(
+    board: &mut Board,
+    mine_count: usize,
+    first_click: usize,
+    config: &Config,
+    rng: &mut StdRng,
+) -> bool {
+    let attempts = if config.easy_start {
+        EASY_START_ATTEMPTS
+    } else {
+        1
+    };
+    let mut solvable = true;
+
+    for attempt in 1..=attempts {
+        solvable = if config.no_guess {
+            solver::generate_no_guess_mines(
+                board,
+                mine_count,
+                first_click,
+                config.first_click_safe,
+                NO_GUESS_ATTEMPTS,
+                rng,
+            )
+        } else {
+            board.generate_mines(mine_count, first_click, config.first_click_safe, rng);
+            true
+        };
 
-    input = input.trim().to_lowercase();
+        if !board.is_mine(first_click) && board.count_neighbouring_mines(first_click) == 0 {
+            break;
+        }
 
-    if input == "exit" {
-        return Ok(InputAction::Exit);
+        if attempt == attempts && config.easy_start {
+            log::warn!("No layout opening an area at the first click found");
+        }
     }
 
-    let parts = input.split(' ').collect::<Vec<_>>();
+    solvable
+}
 
-    if parts.len() != 3 {
-        return Err(InputError::ParseError);
+fn autoplay(config: &Config, render_options: &RenderOptions) -> Result<(), Error> {
+    let mut board = Board::new(config.row_count, config.col_count);
+    board.set_wrap(config.wrap);
+    board.set_connectivity(config.connectivity);
+
+    let first_click =
+        Coord::new(config.row_count / 2, config.col_count / 2).to_index(config.col_count);
+    place_mines(
+        &mut board,
+        config.mine_count,
+        first_click,
+        config,
+        &mut create_rng(config.seed),
+    );
+
+    let report = solver::autoplay(&mut board, first_click);
+    let messages = render_options.messages;
+
+    print_board(&mut io::stdout().lock(), &board, render_options, None)?;
+    println!(
+        "\n{}",
+        if report.won {
+            messages.result_won
+        } else {
+            messages.result_lost
+        }
+    );
+    println!(
+        "{}",
+        fill(messages.autoplay_deductions, &[&report.deductions])
+    );
+    println!("{}", fill(messages.autoplay_guesses, &[&report.guesses]));
+
+    Ok(())
+}
+
+/// Plays the moves of a recorded game on the board generated from the recorded seed and
+/// prints the final board.
+fn play_replay(path: &str, config: &Config, render_options: &RenderOptions) -> Result<(), Error> {
+    let recording = replay::load(path)?;
+
+    let mut config = config.clone();
+    recording.settings.apply(&mut config);
+    config::validate_config(
+        config.row_count,
+        config.col_count,
+        config.mine_count,
+        config.first_click_safe,
+        render_options.messages,
+    )?;
+
+    let mut state = GameState::new(config.row_count, config.col_count, config.mine_count);
+    state.board.set_wrap(config.wrap);
+    state.board.set_connectivity(config.connectivity);
+    state.set_lives(config.lives);
+    if config.versus {
+        state.set_versus();
     }
+    state.set_reveal_budget(config.reveal_budget);
+    let mut rng = StdRng::seed_from_u64(recording.settings.seed);
 
-    let row = parts[1].parse::<usize>().or(Err(InputError::ParseError))?;
-    let col = parts[2].parse::<usize>().or(Err(InputError::ParseError))?;
+    replay_moves(
+        &mut state,
+        recording
+            .moves
+            .iter()
+            .map(|&(_, recorded_move)| recorded_move),
+        &config,
+        &mut rng,
+    );
+
+    print_board(&mut io::stdout().lock(), &state.board, render_options, None)?;
+    print_status(&mut io::stdout().lock(), &state, render_options)?;
+
+    let messages = render_options.messages;
+    println!(
+        "\n{}",
+        fill(
+            messages.replay_result,
+            &[&result_text(state.status, messages), &recording.moves.len()]
+        )
+    );
+
+    Ok(())
+}
 
-    if row >= row_size || col >= col_size {
-        return Err(InputError::InvalidCoords((row, col)));
+/// Plays back the moves of an analysis snapshot written by `--export-json` on its
+/// recorded mine layout and prints the final board next to the recorded result.
+fn play_replay_json(
+    path: &str,
+    config: &Config,
+    render_options: &RenderOptions,
+) -> Result<(), Error> {
+    let analysis = Analysis::load(path)?;
+    let (state, moves) = replay_analysis(&analysis, config)?;
+
+    print_board(&mut io::stdout().lock(), &state.board, render_options, None)?;
+    print_status(&mut io::stdout().lock(), &state, render_options)?;
+    let messages = render_options.messages;
+    println!(
+        "\n{}",
+        fill(
+            messages.replay_json_result,
+            &[
+                &result_text(state.status, messages),
+                &moves,
+                &result_text(analysis.status.into(), messages)
+            ]
+        )
+    );
+
+    Ok(())
+}
+
+/// Rebuilds the board of an exported game and replays its moves on it. Returns the
+/// replayed game and the number of moves.
+fn replay_analysis(analysis: &Analysis, config: &Config) -> Result<(GameState, usize), Error> {
+    let moves = analysis.moves()?;
+    config::validate_config(
+        analysis.rows,
+        analysis.cols,
+        analysis.mines,
+        0,
+        config.language.messages(),
+    )?;
+
+    let mut config = config.clone();
+    config.row_count = analysis.rows;
+    config.col_count = analysis.cols;
+    config.mine_count = analysis.mines;
+    config.question_marks = analysis.question_marks;
+    config.strict_flags = analysis.strict_flags;
+
+    let mut state = GameState::new(analysis.rows, analysis.cols, analysis.mines);
+    state.board.set_wrap(analysis.wrap);
+    state.board.set_connectivity(analysis.connectivity()?);
+    state.board.set_mines(&analysis.mine_indices());
+    state.set_lives(analysis.lives);
+    state.set_reveal_budget(analysis.reveal_budget);
+    let mut rng = StdRng::seed_from_u64(analysis.seed);
+
+    replay_moves(&mut state, moves.iter().copied(), &config, &mut rng);
+
+    Ok((state, moves.len()))
+}
+
+fn result_text(status: GameStatus, messages: &Messages) -> &'static str {
+    match status {
+        GameStatus::Playing => messages.result_playing,
+        GameStatus::Won => messages.result_won,
+        GameStatus::Lost => messages.result_lost,
     }
+}
 
-    match parts[0] {
-        "f" => Ok(InputAction::Flag(row * row_size + col)),
-        "x" => Ok(InputAction::Open(row * row_size + col)),
-        _ => Err(InputError::ParseError),
+/// Applies recorded moves until the game is over.
+fn replay_moves(
+    state: &mut GameState,
+    moves: impl Iterator<Item = Move>,
+    config: &Config,
+    rng: &mut StdRng,
+) {
+    for recorded_move in moves {
+        if state.status != GameStatus::Playing {
+            break;
+        }
+
+        match recorded_move {
+            Move::Open(coord) => {
+                open_tile(state, coord.to_index(config.col_count), config, rng, |_| {});
+            }
+            Move::Flag(coord) => {
+                flag_tile(state, coord.to_index(config.col_count), config);
+            }
+            Move::Chord(coord) => state.chord(coord.to_index(config.col_count)),
+            Move::Undo => {
+                state.undo();
+            }
+            Move::Autoflag => {
+                let _ = state.autoflag();
+            }
+            Move::Continue => {
+                state.continue_reveal();
+            }
+            Move::Assist => {
+                state.assist();
+            }
+        }
     }
 }
 
-fn print_parser_error(error: InputError) {
-    println!();
+/// The seed for the next board: the configured one, or a random one so that the board
+/// can still be shared as a code.
+fn new_seed(config: &Config) -> u64 {
+    config.seed.unwrap_or_else(rand::random)
+}
 
-    match error {
-        InputError::ParseError => println!("Failed to parse the input. Please try again"),
-        InputError::InvalidCoords(coords) => println!("The coords {:?} are invalid", coords),
-    };
+fn log_new_game(state: &GameState, seed: u64) {
+    log::info!(
+        "New {}x{} game with {} mines, seed {}",
+        state.board.row_count(),
+        state.board.col_count(),
+        state.mine_count,
+        seed
+    );
+}
 
-    let mut buf = String::new();
-    io::stdin()
-        .read_line(&mut buf)
-        .expect("Failed to read input");
+fn create_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
 }
 
-fn generate_mine_positions(
-    count: usize,
-    index_to_avoid: usize,
-    row_count: usize,
-    col_count: usize,
-) -> HashSet<usize> {
-    let mut rng = rand::thread_rng();
+/// Clears the terminal between turns. Writes a separator line instead if clearing is
+/// disabled or fails, keeping the earlier turns readable in a log.
+fn clear_screen(writer: &mut impl Write, options: &RenderOptions) -> io::Result<()> {
+    // A screen reader would read the separator line out loud.
+    if options.accessible {
+        return Ok(());
+    }
+
+    if !options.clear_screen || clearscreen::clear().is_err() {
+        writeln!(writer, "\n{}", "-".repeat(40))?;
+    }
+
+    Ok(())
+}
 
-    let indices_to_avoid = get_neighbouring_indices(index_to_avoid, row_count, col_count);
+fn request_play_again(input: &mut impl InputSource, messages: &Messages) -> Result<bool, Error> {
+    loop {
+        println!("\n{}", messages.play_again);
 
-    let indices = (0..row_count * col_count)
-        .filter(|idx| !indices_to_avoid.contains(idx) || *idx != index_to_avoid)
-        .choose_multiple(&mut rng, count);
+        let line = input.read_line()?;
+        if line.is_empty() {
+            return Ok(false);
+        }
 
-    HashSet::from_iter(indices)
+        match line.trim().to_lowercase().as_str() {
+            "y" | "yes" | "j" | "ja" => return Ok(true),
+            "n" | "no" | "nein" => return Ok(false),
+            _ => {}
+        }
+    }
 }
 
-fn get_neighbouring_indices(index: usize, row_count: usize, col_count: usize) -> HashSet<usize> {
-    let max_index = row_count * col_count -1;
-    HashSet::from_iter(vec![
-        index.saturating_sub(col_count + 1),
-        index.saturating_sub(col_count),
-        index.saturating_sub(col_count - 1),
-        index.saturating_sub(1),
-        index.saturating_add(1).clamp(0, max_index),
-        index.saturating_add(col_count - 1).clamp(0, max_index),
-        index.saturating_add(col_count).clamp(0, max_index),
-        index.saturating_add(col_count + 1).clamp(0, max_index),
-    ])
+fn print_board(
+    writer: &mut impl Write,
+    board: &Board,
+    options: &RenderOptions,
+    highlight: Option<usize>,
+) -> io::Result<()> {
+    if options.accessible {
+        return print_accessible_board(writer, board, options, highlight);
+    }
+
+    if options.compact {
+        return print_compact_board(writer, board, options);
+    }
+
+    print_grid(writer, board, options, |tile_idx, tile, cell_width| {
+        render_cell(tile, highlight == Some(tile_idx), options, cell_width)
+    })
 }
 
-fn count_neighbouring_mines(
-    tile_idx: usize,
-    bomb_indices: &HashSet<usize>,
-    row_count: usize,
-    col_count: usize,
-) -> usize {
-    get_neighbouring_indices(tile_idx, row_count, col_count)
+/// Prints the board with the frontier tiles shaded by their estimated chance of being a
+/// mine, given as `(tile, chance)` pairs, see [`heat_shade`]. The shade is written as a
+/// digit and, with colors enabled, as the background from green to red.
+fn print_heatmap(
+    writer: &mut impl Write,
+    board: &Board,
+    chances: &[(usize, f64)],
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let frontier = board.frontier();
+    let mut shades = vec![None; board.tiles().len()];
+    for &(tile_idx, chance) in chances {
+        if frontier.binary_search(&tile_idx).is_ok() {
+            shades[tile_idx] = Some(heat_shade(chance));
+        }
+    }
+
+    print_grid(writer, board, options, |tile_idx, tile, cell_width| {
+        let Some(shade) = shades[tile_idx] else {
+            return render_cell(tile, false, options, cell_width);
+        };
+
+        let symbol = format!("{:>width$}", shade, width = cell_width);
+        if options.use_color {
+            format!("\x1b[97;48;5;{}m{}\x1b[0m", HEAT_COLORS[shade], symbol)
+        } else {
+            symbol
+        }
+    })
+}
+
+/// Maps the chance of a mine to the shade of the heatmap, 0 for a safe tile to 9 for a
+/// certain mine.
+fn heat_shade(chance: f64) -> usize {
+    (chance.clamp(0.0, 1.0) * 9.0).round() as usize
+}
+
+/// Prints the column labels and one line per row, rendering every tile with `render`,
+/// which gets the index of the tile, the tile and the width of a cell.
+fn print_grid(
+    writer: &mut impl Write,
+    board: &Board,
+    options: &RenderOptions,
+    render: impl Fn(usize, &Tile, usize) -> String,
+) -> io::Result<()> {
+    let col_count = board.col_count();
+    let col_labels = column_labels(board, options);
+    let row_label_width = row_label_width(board);
+    let cell_width = cell_width(board, options, &col_labels);
+
+    let header = col_labels
         .iter()
-        .filter(|idx| bomb_indices.contains(idx))
-        .count()
+        .map(|label| format!("{:>width$}", label, width = cell_width))
+        .collect::<Vec<_>>()
+        .join(&options.separator);
+
+    let rows = board
+        .tiles()
+        .chunks(col_count)
+        .enumerate()
+        .map(|(row_idx, row)| {
+            let tiles = row
+                .iter()
+                .enumerate()
+                .map(|(col_idx, tile)| {
+                    render(
+                        Coord::new(row_idx, col_idx).to_index(col_count),
+                        tile,
+                        cell_width,
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(&options.separator);
+
+            format!("{:>width$} {}", row_idx, tiles, width = row_label_width)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    writeln!(writer, "{:width$} {}", "", header, width = row_label_width)?;
+    writeln!(writer, "{}", rows)
+}
+
+/// Prints the zoomed out board of `--compact`. The rows are labelled with the first tile
+/// row of each line, the columns every ten tiles.
+fn print_compact_board(
+    writer: &mut impl Write,
+    board: &Board,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let row_label_width = row_label_width(board);
+
+    let mut header = String::new();
+    for (col, label) in column_labels(board, options).iter().enumerate().step_by(10) {
+        let padding = (col / braille::CELL_COLS).saturating_sub(header.chars().count());
+        header = format!("{}{}{}", header, " ".repeat(padding), label);
+    }
+
+    writeln!(writer, "{:width$} {}", "", header, width = row_label_width)?;
+    for (line_idx, line) in braille::render(board).iter().enumerate() {
+        let row = line_idx * braille::CELL_ROWS;
+        writeln!(writer, "{:>width$} {}", row, line, width = row_label_width)?;
+    }
+
+    Ok(())
+}
+
+/// Describes the board in words, one line per row, for screen readers.
+fn print_accessible_board(
+    writer: &mut impl Write,
+    board: &Board,
+    options: &RenderOptions,
+    highlight: Option<usize>,
+) -> io::Result<()> {
+    let messages = options.messages;
+
+    writeln!(writer)?;
+    for (row_idx, row) in board.tiles().chunks(board.col_count()).enumerate() {
+        let tiles = row
+            .iter()
+            .map(|tile| match tile {
+                Tile::Concealed => messages.word_concealed.to_string(),
+                Tile::Flagged => messages.word_flagged.to_string(),
+                Tile::Question => messages.word_question.to_string(),
+                Tile::Open(0) => messages.word_empty.to_string(),
+                Tile::Open(mine_count) => mine_count.to_string(),
+                Tile::Mine => messages.word_mine.to_string(),
+                Tile::TriggeredMine => messages.word_triggered_mine.to_string(),
+                Tile::WrongFlag => messages.word_wrong_flag.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(writer, "{}", fill(messages.row, &[&row_idx, &tiles]))?;
+    }
+
+    if let Some(tile_index) = highlight {
+        let coords = format_coords(
+            Coord::from_index(tile_index, board.col_count()),
+            options.coord_style,
+        );
+        writeln!(writer, "{}", fill(messages.highlighted, &[&coords]))?;
+    }
+
+    Ok(())
+}
+
+/// Puts a move that was just made into words for the last action line, given the board
+/// after the move and the number of tiles it revealed. Moves that do not touch the board
+/// are not described.
+fn describe_action(
+    action: &InputAction,
+    board: &Board,
+    revealed: usize,
+    options: &RenderOptions,
+) -> Option<String> {
+    let messages = options.messages;
+    let coords = |tile_index| {
+        format_coords(
+            Coord::from_index(tile_index, board.col_count()),
+            options.coord_style,
+        )
+    };
+
+    match *action {
+        InputAction::Open(tile_index) => match board.tiles()[tile_index] {
+            Tile::Mine | Tile::TriggeredMine => {
+                Some(fill(messages.action_hit_mine, &[&coords(tile_index)]))
+            }
+            Tile::Open(_) => Some(fill(
+                messages.action_open,
+                &[&coords(tile_index), &revealed],
+            )),
+            _ => None,
+        },
+        InputAction::Flag(tile_index) => {
+            let text = match board.tiles()[tile_index] {
+                Tile::Flagged => messages.action_flag,
+                Tile::Question => messages.action_question,
+                Tile::Concealed => messages.action_unmark,
+                _ => return None,
+            };
+            Some(fill(text, &[&coords(tile_index)]))
+        }
+        InputAction::Chord(tile_index) => Some(fill(
+            messages.action_chord,
+            &[&coords(tile_index), &revealed],
+        )),
+        InputAction::Continue => Some(fill(messages.action_continue, &[&revealed])),
+        InputAction::Undo => Some(messages.action_undo.to_string()),
+        _ => None,
+    }
+}
+
+fn column_labels(board: &Board, options: &RenderOptions) -> Vec<String> {
+    (0..board.col_count())
+        .map(|col| match options.coord_style {
+            CoordStyle::Numbers => col.to_string(),
+            CoordStyle::Letters => column_label(col).to_uppercase(),
+        })
+        .collect()
+}
+
+fn row_label_width(board: &Board) -> usize {
+    (board.row_count() - 1).to_string().len()
+}
+
+/// Width of every cell, so that the widest column label or tile symbol still fits.
+fn cell_width(board: &Board, options: &RenderOptions, col_labels: &[String]) -> usize {
+    let symbols = board
+        .tiles()
+        .iter()
+        .map(|tile| tile_symbol(tile, options))
+        .collect::<Vec<_>>();
+
+    col_labels
+        .iter()
+        .chain(symbols.iter())
+        .map(|symbol| symbol.chars().count())
+        .max()
+        .unwrap_or(1)
+}
+
+fn render_cell(
+    tile: &Tile,
+    is_highlighted: bool,
+    options: &RenderOptions,
+    cell_width: usize,
+) -> String {
+    let symbol = match tile {
+        _ if is_highlighted && !options.use_color => "+".to_string(),
+        _ => tile_symbol(tile, options),
+    };
+    let symbol = format!("{:>width$}", symbol, width = cell_width);
+
+    match tile_color(tile, &options.number_colors) {
+        _ if is_highlighted && options.use_color => format!("\x1b[7m{}\x1b[0m", symbol),
+        Some(color) if options.use_color => format!("\x1b[{}m{}\x1b[0m", color, symbol),
+        _ => symbol,
+    }
+}
+
+fn tile_symbol(tile: &Tile, options: &RenderOptions) -> String {
+    let symbols = &options.symbols;
+
+    match tile {
+        Tile::Open(0) => options.zero_char.to_string(),
+        Tile::Flagged => symbols.flagged.clone(),
+        Tile::Question => symbols.question.clone(),
+        Tile::Open(count) => count.to_string(),
+        Tile::Concealed => symbols.concealed.clone(),
+        Tile::Mine => symbols.mine.clone(),
+        Tile::TriggeredMine => "@".to_string(),
+        Tile::WrongFlag => "X".to_string(),
+    }
+}
+
+fn tile_color(tile: &Tile, number_colors: &NumberColors) -> Option<&'static str> {
+    match tile {
+        Tile::Open(mine_count) => number_colors.color(*mine_count),
+        Tile::Flagged | Tile::WrongFlag => Some("31"),
+        Tile::Mine => Some("1"),
+        Tile::TriggeredMine => Some("1;31"),
+        _ => None,
+    }
+}
+
+fn print_status(
+    writer: &mut impl Write,
+    state: &GameState,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let messages = options.messages;
+    let board = &state.board;
+    let mine_count = state.mine_count;
+    let flag_count = board.count_flags();
+    let remaining = mine_count as i64 - flag_count as i64;
+
+    let mut status = fill(
+        messages.status,
+        &[
+            &mine_count,
+            &flag_count,
+            &remaining,
+            &state.elapsed_seconds(),
+        ],
+    );
+
+    if state.max_lives() > 1 {
+        let lives = fill(messages.lives, &[&state.lives]);
+        status = format!("{}  {}", status, lives);
+    }
+
+    if options.show_progress {
+        let safe_total = board.row_count() * board.col_count() - mine_count;
+        let progress = fill(messages.progress, &[&board.count_open(), &safe_total]);
+        status = format!("{}  {}", status, progress);
+    }
+
+    writeln!(writer, "\n{}", status)?;
+
+    if state.has_pending_reveal() {
+        writeln!(writer, "{}", messages.reveal_paused)?;
+    }
+
+    if let Some(versus) = state.versus() {
+        let turn = fill(
+            messages.versus_turn,
+            &[
+                &(versus.current_player + 1),
+                &versus.opened[0],
+                &versus.opened[1],
+            ],
+        );
+        writeln!(writer, "{}", turn)?;
+    }
+
+    Ok(())
+}
+
+fn print_game_over(state: &GameState, options: &RenderOptions, message: &str) -> Result<(), Error> {
+    clear_screen(&mut io::stdout().lock(), options)?;
+    print_board(&mut io::stdout().lock(), &state.board, options, None)?;
+    let messages = options.messages;
+    println!("\n{}", message);
+    println!("{}", fill(messages.time, &[&state.elapsed_seconds()]));
+    println!("{}", fill(messages.hints_used, &[&state.hints_used]));
+    if state.assists_used > 0 {
+        println!("{}", fill(messages.assists_used, &[&state.assists_used]));
+    }
+    println!(
+        "{}",
+        fill(messages.wrong_flags, &[&state.board.count_wrong_flags()])
+    );
+
+    if state.status == GameStatus::Won {
+        let three_bv = state.board.three_bv();
+        let efficiency = three_bv as f64 / state.clicks.max(1) as f64 * 100.0;
+        println!("{}", fill(messages.three_bv, &[&three_bv]));
+        println!("{}", fill(messages.clicks, &[&state.clicks]));
+        println!(
+            "{}",
+            fill(messages.efficiency, &[&format!("{:.0}", efficiency)])
+        );
+    }
+
+    Ok(())
+}
+
+fn record_score(
+    input: &mut impl InputSource,
+    state: &GameState,
+    messages: &Messages,
+) -> Result<(), Error> {
+    let mut high_scores = match HighScores::load() {
+        Ok(high_scores) => high_scores,
+        Err(error) => {
+            println!("{}", fill(messages.scores_load_failed, &[&error]));
+            return Ok(());
+        }
+    };
+
+    let label = scores::board_label(
+        state.board.row_count(),
+        state.board.col_count(),
+        state.mine_count,
+    );
+    let seconds = state.elapsed_seconds();
+
+    if high_scores.qualifies(&label, seconds) {
+        println!("\n{}", messages.new_high_score);
+
+        let name = input.read_line()?;
+        let name = name.trim();
+
+        let score = Score {
+            seconds,
+            name: (!name.is_empty()).then(|| name.to_string()),
+        };
+        high_scores.insert(&label, score);
+
+        if let Err(error) = high_scores.save() {
+            println!("{}", fill(messages.scores_save_failed, &[&error]));
+        }
+    }
+
+    println!();
+    high_scores.print_table(&label, messages);
+
+    Ok(())
+}
+
+fn coords_syntax(coord_style: CoordStyle) -> &'static str {
+    match coord_style {
+        CoordStyle::Numbers => "<row> <col>",
+        CoordStyle::Letters => "<col><row>",
+    }
+}
+
+fn print_menu(
+    writer: &mut impl Write,
+    coord_style: CoordStyle,
+    messages: &Messages,
+) -> io::Result<()> {
+    let coords = coords_syntax(coord_style);
+
+    writeln!(writer, "\n")?;
+    writeln!(writer, "{}", fill(messages.menu_open, &[&coords]))?;
+    writeln!(writer, "{}", fill(messages.menu_flag, &[&coords]))?;
+    writeln!(writer, "{}", messages.menu_help)?;
+    writeln!(writer, "\n")
+}
+
+fn print_help(
+    writer: &mut impl Write,
+    coord_style: CoordStyle,
+    messages: &Messages,
+) -> io::Result<()> {
+    let coords = coords_syntax(coord_style);
+    let example = match coord_style {
+        CoordStyle::Numbers => "x 3 4",
+        CoordStyle::Letters => "x d3",
+    };
+
+    writeln!(writer, "\n")?;
+    writeln!(writer, "{}", fill(messages.help_title, &[&example]))?;
+    writeln!(writer, "  x {:<12} {}", coords, messages.help_open)?;
+    writeln!(writer, "  x {:<12} {}", "<index>", messages.help_open_index)?;
+    writeln!(writer, "  f {:<12} {}", coords, messages.help_flag)?;
+    writeln!(writer, "  c {:<12} {}", coords, messages.help_chord)?;
+    writeln!(writer, "  info {:<9} {}", coords, messages.help_info)?;
+    writeln!(writer, "  {:<14} {}", "hint", messages.help_hint)?;
+    writeln!(writer, "  {:<14} {}", "guess", messages.help_guess)?;
+    writeln!(writer, "  {:<14} {}", "heatmap", messages.help_heatmap)?;
+    writeln!(writer, "  {:<14} {}", "autoflag", messages.help_autoflag)?;
+    writeln!(writer, "  {:<14} {}", "assist", messages.help_assist)?;
+    writeln!(writer, "  {:<14} {}", "continue", messages.help_continue)?;
+    writeln!(writer, "  {:<14} {}", "undo", messages.help_undo)?;
+    writeln!(writer, "  {:<14} {}", "save <file>", messages.help_save)?;
+    writeln!(
+        writer,
+        "  {:<14} {}",
+        "snapshot <file>", messages.help_snapshot
+    )?;
+    writeln!(writer, "  {:<14} {}", "code", messages.help_code)?;
+    writeln!(writer, "  {:<14} {}", "restart", messages.help_restart)?;
+    writeln!(writer, "  {:<14} {}", "Enter, !!, up", messages.help_repeat)?;
+    writeln!(writer, "  {:<14} {}", "help, ?", messages.help_help)?;
+    writeln!(writer, "  {:<14} {}", "exit", messages.help_exit)?;
+    writeln!(writer, "{}", messages.help_batch)?;
+    writeln!(writer, "\n")
+}
+
+/// Reads and parses the next line. An empty line, `!!` and `up` repeat `last_input`, the
+/// last line that was parsed successfully. It is parsed again, so a command that no
+/// longer fits the board fails like a newly typed one.
+fn request_input(
+    source: &mut impl InputSource,
+    row_count: usize,
+    col_count: usize,
+    coord_style: CoordStyle,
+    last_input: &mut Option<String>,
+) -> Result<Vec<InputAction>, InputError> {
+    let input = source.read_line().map_err(InputError::Io)?;
+
+    if input.is_empty() {
+        return Ok(vec![InputAction::Exit]);
+    }
+
+    let input = match input.trim() {
+        "" | "!!" | "up" => last_input.clone().ok_or(InputError::NoPreviousCommand)?,
+        _ => input,
+    };
+
+    let actions = parse_line(&input, row_count, col_count, coord_style)?;
+    *last_input = Some(input);
+
+    Ok(actions)
+}
+
+/// Parses a line of one or more commands separated by `;`. Nothing is executed if any of
+/// them fails to parse.
+fn parse_line(
+    input: &str,
+    row_count: usize,
+    col_count: usize,
+    coord_style: CoordStyle,
+) -> Result<Vec<InputAction>, InputError> {
+    let segments = input
+        .split(';')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+    if segments.len() <= 1 {
+        let segment = segments.first().copied().unwrap_or_default();
+        return Ok(vec![parse_action(
+            segment,
+            row_count,
+            col_count,
+            coord_style,
+        )?]);
+    }
+
+    segments
+        .iter()
+        .enumerate()
+        .map(|(idx, segment)| {
+            parse_action(segment, row_count, col_count, coord_style).map_err(|error| {
+                InputError::InBatch {
+                    segment: idx + 1,
+                    error: Box::new(error),
+                }
+            })
+        })
+        .collect()
+}
+
+fn parse_action(
+    input: &str,
+    row_count: usize,
+    col_count: usize,
+    coord_style: CoordStyle,
+) -> Result<InputAction, InputError> {
+    if let Some(("save", path)) = input.split_once(char::is_whitespace) {
+        return Ok(InputAction::Save(path.trim().to_string()));
+    }
+
+    if let Some(("snapshot", path)) = input.split_once(char::is_whitespace) {
+        return Ok(InputAction::Snapshot(path.trim().to_string()));
+    }
+
+    let input = input.to_lowercase();
+
+    if input == "exit" {
+        return Ok(InputAction::Exit);
+    }
+
+    if input == "undo" {
+        return Ok(InputAction::Undo);
+    }
+
+    if input == "help" || input == "?" {
+        return Ok(InputAction::Help);
+    }
+
+    if input == "code" {
+        return Ok(InputAction::Code);
+    }
+
+    if input == "guess" {
+        return Ok(InputAction::Guess);
+    }
+
+    if input == "heatmap" {
+        return Ok(InputAction::Heatmap);
+    }
+
+    if input == "autoflag" {
+        return Ok(InputAction::Autoflag);
+    }
+
+    if input == "assist" {
+        return Ok(InputAction::Assist);
+    }
+
+    if input == "continue" {
+        return Ok(InputAction::Continue);
+    }
+
+    if input == "hint" {
+        return Ok(InputAction::Hint);
+    }
+
+    if input == "restart" {
+        return Ok(InputAction::Restart);
+    }
+
+    let parts = input.split_whitespace().collect::<Vec<_>>();
+
+    // A single number after the command is a flat tile index, in either coordinate style.
+    if let [command, index] = parts[..] {
+        if index.chars().all(|c| c.is_ascii_digit()) {
+            let index = index.parse::<usize>().or(Err(InputError::ParseError))?;
+            let tile_count = row_count * col_count;
+
+            if index >= tile_count {
+                return Err(InputError::IndexOutOfRange { index, tile_count });
+            }
+
+            return parse_command(command, index);
+        }
+    }
+
+    let (command, coord) = match coord_style {
+        CoordStyle::Numbers => parse_numeric_coords(&input)?,
+        CoordStyle::Letters => parse_letter_coords(&input)?,
+    };
+
+    if coord.row >= row_count {
+        return Err(InputError::RowOutOfRange {
+            row: coord.row,
+            row_count,
+        });
+    }
+
+    if coord.col >= col_count {
+        return Err(InputError::ColOutOfRange {
+            col: coord.col,
+            col_count,
+        });
+    }
+
+    parse_command(command, coord.to_index(col_count))
+}
+
+fn parse_command(command: &str, tile_index: usize) -> Result<InputAction, InputError> {
+    match command {
+        "f" => Ok(InputAction::Flag(tile_index)),
+        "x" => Ok(InputAction::Open(tile_index)),
+        "c" => Ok(InputAction::Chord(tile_index)),
+        "info" => Ok(InputAction::Info(tile_index)),
+        _ => Err(InputError::ParseError),
+    }
+}
+
+fn parse_numeric_coords(input: &str) -> Result<(&str, Coord), InputError> {
+    let parts = input.split_whitespace().collect::<Vec<_>>();
+
+    if parts.len() != 3 {
+        return Err(InputError::ParseError);
+    }
+
+    let row = parts[1].parse::<usize>().or(Err(InputError::ParseError))?;
+    let col = parts[2].parse::<usize>().or(Err(InputError::ParseError))?;
+
+    Ok((parts[0], Coord::new(row, col)))
+}
+
+fn parse_letter_coords(input: &str) -> Result<(&str, Coord), InputError> {
+    if input.is_empty() {
+        return Ok(("", Coord::new(0, 0)));
+    }
+
+    // Single letter commands may be written without a space, like 'xd3'. The first
+    // character may take more than one byte, which `split_at` has to respect.
+    let (command, coords) = input
+        .split_once(char::is_whitespace)
+        .unwrap_or_else(|| input.split_at(input.chars().next().map_or(0, char::len_utf8)));
+    let coords = coords
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    let row_start = coords
+        .find(|c: char| c.is_ascii_digit())
+        .ok_or(InputError::ParseError)?;
+    let (col_label, row) = coords.split_at(row_start);
+
+    let col = parse_column_label(col_label).ok_or(InputError::ParseError)?;
+    let row = row.parse::<usize>().or(Err(InputError::ParseError))?;
+
+    Ok((command, Coord::new(row, col)))
+}
+
+fn format_coords(coord: Coord, coord_style: CoordStyle) -> String {
+    match coord_style {
+        CoordStyle::Numbers => format!("({}, {})", coord.row, coord.col),
+        CoordStyle::Letters => format!("{}{}", column_label(coord.col).to_uppercase(), coord.row),
+    }
+}
+
+fn column_label(col: usize) -> String {
+    let mut label = Vec::new();
+    let mut remaining = col + 1;
+
+    while remaining > 0 {
+        remaining -= 1;
+        label.push((b'a' + (remaining % 26) as u8) as char);
+        remaining /= 26;
+    }
+
+    label.iter().rev().collect()
+}
+
+fn parse_column_label(label: &str) -> Option<usize> {
+    if label.is_empty() || !label.chars().all(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+
+    label
+        .bytes()
+        .try_fold(0_usize, |col, letter| {
+            col.checked_mul(26)?
+                .checked_add((letter - b'a') as usize + 1)
+        })
+        .map(|col| col - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use cli_minesweeper::{Connectivity, StringInput};
+    use config::ColorMode;
+    use messages::Language;
+    use temp_file::TempFile;
+
+    use super::*;
+
+    fn plain_options() -> RenderOptions {
+        RenderOptions::new(&Config {
+            color: ColorMode::Never,
+            clear_screen: false,
+            ..Config::default()
+        })
+    }
+
+    fn render(board: &Board, options: &RenderOptions) -> String {
+        let mut output = Vec::new();
+        print_board(&mut output, board, options, None).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn letter_coords_reject_a_multi_byte_first_character() {
+        assert!(matches!(
+            parse_action("é", 5, 5, CoordStyle::Letters),
+            Err(InputError::ParseError)
+        ));
+        assert!(matches!(
+            parse_action("éb2", 5, 5, CoordStyle::Letters),
+            Err(InputError::ParseError)
+        ));
+        assert!(matches!(
+            parse_action("xb2", 5, 5, CoordStyle::Letters),
+            Ok(InputAction::Open(11))
+        ));
+    }
+
+    #[test]
+    fn windows_line_endings_are_trimmed() {
+        let mut input = StringInput::new(["x 1 2\r", "f b1\r"]);
+        let mut last_input = None;
+
+        let actions = request_input(&mut input, 3, 4, CoordStyle::Numbers, &mut last_input);
+        assert!(matches!(actions.as_deref(), Ok([InputAction::Open(6)])));
+
+        let actions = request_input(&mut input, 3, 4, CoordStyle::Letters, &mut last_input);
+        assert!(matches!(actions.as_deref(), Ok([InputAction::Flag(5)])));
+
+        assert!(matches!(
+            parse_action("x 1 2\r\n", 3, 4, CoordStyle::Numbers),
+            Ok(InputAction::Open(6))
+        ));
+    }
+
+    #[test]
+    fn board_renders_like_the_golden_output() {
+        let mut board: Board = "*##\n###\nF##".parse().unwrap();
+        board.open(8);
+
+        assert_eq!(
+            render(&board, &plain_options()),
+            "  0 1 2\n\
+             0 # 1  \n\
+             1 1 1  \n\
+             2 F    \n"
+        );
+
+        board.open(0);
+        assert_eq!(
+            render(&board, &plain_options()),
+            "  0 1 2\n\
+             0 @ 1  \n\
+             1 1 1  \n\
+             2 X    \n"
+        );
+    }
+
+    #[test]
+    fn mines_are_placed_by_the_first_open() {
+        let config = Config::default();
+        let mut rng = StdRng::seed_from_u64(44);
+        let mut state = GameState::new(9, 9, 10);
+
+        assert!(!state.board.has_mines());
+        assert_eq!(state.board.mine_count(), 0);
+
+        open_tile(&mut state, 40, &config, &mut rng, |_| {});
+
+        assert!(state.board.has_mines());
+        assert_eq!(state.board.mine_count(), 10);
+        assert!(!state.board.is_mine(40));
+        assert_eq!(state.board.tiles()[40], Tile::Open(0));
+    }
+
+    #[test]
+    fn every_bad_input_produces_an_error() {
+        let mut input = StringInput::new(["x 9 9", "bogus", "x 0 0"]);
+        let mut last_input = None;
+        let mut next = || request_input(&mut input, 3, 3, CoordStyle::Numbers, &mut last_input);
+
+        assert!(matches!(
+            next(),
+            Err(InputError::RowOutOfRange {
+                row: 9,
+                row_count: 3
+            })
+        ));
+        assert!(matches!(next(), Err(InputError::ParseError)));
+        assert!(matches!(next().as_deref(), Ok([InputAction::Open(0)])));
+        assert!(matches!(next().as_deref(), Ok([InputAction::Exit])));
+    }
+
+    fn play_script(board: &str, commands: &[&str]) -> GameState {
+        play_puzzle(board, &mut StringInput::new(commands.iter().copied()))
+    }
+
+    fn play_puzzle(board: &str, input: &mut impl InputSource) -> GameState {
+        let config = Config {
+            color: ColorMode::Never,
+            clear_screen: false,
+            quiet: true,
+            seed: Some(68),
+            ..Config::default()
+        };
+        let state = GameState::from_puzzle(board.parse().unwrap());
+
+        play(state, &config, &plain_options(), input, true, |_| {}).unwrap()
+    }
+
+    #[test]
+    fn scripted_game_is_won() {
+        let state = play_script("*#*\n###\n###", &["x 2 1", "f 0 0", "f 2", "x 0 1"]);
+
+        assert_eq!(state.status, GameStatus::Won);
+        assert_eq!(
+            render(&state.board, &plain_options()),
+            "  0 1 2\n\
+             0 F 2 F\n\
+             1 1 2 1\n\
+             2      \n"
+        );
+    }
+
+    #[test]
+    fn script_file_plays_a_game_to_the_win() {
+        let file = TempFile::new("txt");
+        fs::write(
+            file.path(),
+            "# Open the bottom row first\nx 2 1\n\nf 0 0\nf 0 2\nx 0 1\n",
+        )
+        .unwrap();
+
+        let state = play_puzzle(
+            "*#*\n###\n###",
+            &mut ScriptInput::open(file.path()).unwrap(),
+        );
+
+        assert_eq!(state.status, GameStatus::Won);
+        assert_eq!(
+            render(&state.board, &plain_options()),
+            "  0 1 2\n\
+             0 F 2 F\n\
+             1 1 2 1\n\
+             2      \n"
+        );
+    }
+
+    #[test]
+    fn scripted_game_is_lost_on_a_mine() {
+        let state = play_script("*#*\n###\n###", &["x 2 1", "x 0 0", "x 0 1"]);
+
+        assert_eq!(state.status, GameStatus::Lost);
+        assert_eq!(state.board.tiles()[0], Tile::TriggeredMine);
+        assert_eq!(state.board.tiles()[1], Tile::Concealed);
+    }
+
+    #[test]
+    fn a_recording_leaves_out_refused_moves() {
+        let file = TempFile::new("txt");
+        let config = Config {
+            color: ColorMode::Never,
+            clear_screen: false,
+            quiet: true,
+            versus: true,
+            record_path: Some(file.path().to_string()),
+            ..Config::default()
+        };
+        let mut state = GameState::from_puzzle("*##\n###\n##*".parse().unwrap());
+        state.set_versus();
+        let mut input = StringInput::new(["x 0 1", "undo", "f 0 1", "x 0 0"]);
+
+        play(state, &config, &plain_options(), &mut input, true, |_| {}).unwrap();
+
+        let recording = replay::load(file.path()).unwrap();
+        assert!(recording.settings.versus);
+        assert_eq!(
+            recording
+                .moves
+                .iter()
+                .map(|&(_, recorded_move)| recorded_move)
+                .collect::<Vec<_>>(),
+            vec![Move::Open(Coord::new(0, 1)), Move::Open(Coord::new(0, 0))]
+        );
+    }
+
+    #[test]
+    fn a_restart_opens_the_first_click_of_a_board_code_again() {
+        let code = BoardCode {
+            row_count: 9,
+            col_count: 9,
+            mine_count: 10,
+            first_click_safe: 1,
+            wrap: false,
+            connectivity: Connectivity::Eight,
+            no_guess: false,
+            easy_start: false,
+            seed: 24,
+            first_click: 40,
+        };
+        let mut config = Config {
+            color: ColorMode::Never,
+            clear_screen: false,
+            quiet: true,
+            code: Some(code),
+            ..Config::default()
+        };
+        code.apply(&mut config);
+        let state = GameState::new(config.row_count, config.col_count, config.mine_count);
+        let mut input = StringInput::new(["restart", "exit"]);
+
+        let state = play(state, &config, &plain_options(), &mut input, true, |_| {}).unwrap();
+
+        assert!(state.board.has_mines());
+        assert!(matches!(state.board.tiles()[40], Tile::Open(_)));
+    }
+
+    #[test]
+    fn redirected_output_gets_a_separator_instead_of_escape_sequences() {
+        let options = plain_options();
+        let board: Board = "*1\n11".parse().unwrap();
+
+        let mut output = Vec::new();
+        clear_screen(&mut output, &options).unwrap();
+        print_board(&mut output, &board, &options, None).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with(&format!("\n{}\n", "-".repeat(40))));
+        assert!(!output.contains('\x1b'));
+
+        let accessible = RenderOptions {
+            accessible: true,
+            ..plain_options()
+        };
+        let mut output = Vec::new();
+        clear_screen(&mut output, &accessible).unwrap();
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn swapped_symbols_change_the_rendering() {
+        let mut board: Board = "F?#*\n####\n###*".parse().unwrap();
+        // Hitting the mine reveals the other one as well.
+        board.open(11);
+        let swapped = RenderOptions {
+            symbols: Symbols {
+                concealed: "F".to_string(),
+                flagged: "#".to_string(),
+                question: "*".to_string(),
+                mine: "?".to_string(),
+            },
+            ..plain_options()
+        };
+
+        assert_eq!(
+            render(&board, &plain_options()),
+            "  0 1 2 3\n\
+             0 X ? # *\n\
+             1 # # # #\n\
+             2 # # # @\n"
+        );
+        assert_eq!(
+            render(&board, &swapped),
+            "  0 1 2 3\n\
+             0 X * F ?\n\
+             1 F F F F\n\
+             2 F F F @\n"
+        );
+    }
+
+    #[test]
+    fn accessible_board_is_described_in_words() {
+        let board: Board = "*1?\nF1#\n000".parse().unwrap();
+        let options = RenderOptions {
+            accessible: true,
+            ..plain_options()
+        };
+
+        let mut output = Vec::new();
+        print_board(&mut output, &board, &options, Some(5)).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\nRow 0: concealed, 1, question mark\n\
+             Row 1: flagged, 1, concealed\n\
+             Row 2: empty, empty, empty\n\
+             Highlighted tile: (1, 2)\n"
+        );
+
+        let german = RenderOptions {
+            messages: Language::German.messages(),
+            ..options
+        };
+        let mut output = Vec::new();
+        print_board(&mut output, &board, &german, None).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with("\nZeile 0: verdeckt, 1, Fragezeichen\n"));
+    }
+
+    /// The (line, column) of every character that differs between two renders.
+    fn changed_cells(before: &str, after: &str) -> Vec<(usize, usize)> {
+        before
+            .lines()
+            .zip(after.lines())
+            .enumerate()
+            .flat_map(|(line, (before, after))| {
+                before
+                    .chars()
+                    .zip(after.chars())
+                    .enumerate()
+                    .filter(|(_, (before, after))| before != after)
+                    .map(move |(column, _)| (line, column))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn actions_change_the_rendered_tile_at_their_coordinates() {
+        for (row_count, col_count, row, col) in [(3, 8, 1, 6), (8, 3, 6, 1)] {
+            // Every other tile is a mine, so opening the target reveals only itself.
+            let target = row * col_count + col;
+            let text = (0..row_count)
+                .map(|r| {
+                    (0..col_count)
+                        .map(|c| {
+                            if r * col_count + c == target {
+                                '#'
+                            } else {
+                                '*'
+                            }
+                        })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let fresh: Board = text.parse().unwrap();
+            // Below the header line, each tile takes two characters after the row label.
+            let expected = vec![(row + 1, 2 + 2 * col)];
+
+            let input = format!("x {row} {col}");
+            let Ok(InputAction::Open(tile_idx)) =
+                parse_action(&input, row_count, col_count, CoordStyle::Numbers)
+            else {
+                panic!("`{input}` is not an open action");
+            };
+            let mut board = fresh.clone();
+            board.open(tile_idx);
+            assert_eq!(
+                changed_cells(
+                    &render(&fresh, &plain_options()),
+                    &render(&board, &plain_options())
+                ),
+                expected,
+                "opening ({row}, {col}) on a {row_count}x{col_count} board"
+            );
+
+            let input = format!("f {row} {col}");
+            let Ok(InputAction::Flag(tile_idx)) =
+                parse_action(&input, row_count, col_count, CoordStyle::Numbers)
+            else {
+                panic!("`{input}` is not a flag action");
+            };
+            let mut board = fresh.clone();
+            board.flag(tile_idx, false);
+            assert_eq!(
+                changed_cells(
+                    &render(&fresh, &plain_options()),
+                    &render(&board, &plain_options())
+                ),
+                expected,
+                "flagging ({row}, {col}) on a {row_count}x{col_count} board"
+            );
+        }
+    }
+
+    #[test]
+    fn a_loss_is_explained_by_a_safe_move_or_a_forced_guess() {
+        // The lower one puts the mine in the corner, which makes the right column safe.
+        let board: Board = "*1#\n11#".parse().unwrap();
+        assert_eq!(
+            explain_loss(&board, &plain_options()),
+            "A safe move existed, e.g. (0, 2)"
+        );
+
+        let board: Board = "*#\n##".parse().unwrap();
+        assert_eq!(
+            explain_loss(&board, &plain_options()),
+            "This loss was unavoidable (forced guess)"
+        );
+    }
+
+    #[test]
+    fn a_chord_loss_is_explained_by_the_wrong_flag() {
+        let mut state = GameState::from_puzzle("*##\n###\n##*".parse().unwrap());
+        state.set_lives(2);
+        state.open(0);
+        state.open(4);
+        state.flag(1, false);
+        assert_eq!(state.lives, 1);
+
+        assert_eq!(
+            explain_chord_loss(&state.board, 4, &plain_options()).as_deref(),
+            Some("The chord relied on the wrong flag at (0, 1)")
+        );
+
+        state.flag(1, false);
+        assert_eq!(explain_chord_loss(&state.board, 4, &plain_options()), None);
+    }
+
+    #[test]
+    fn mine_chances_map_to_heatmap_shades() {
+        let board: Board = "01*##\n011##".parse().unwrap();
+        let shades = solver::mine_chances(&board)
+            .into_iter()
+            .map(|(tile_idx, chance)| (tile_idx, heat_shade(chance)))
+            .collect::<Vec<_>>();
+
+        // The ones on the left pin the mine, the last one in the lower row spreads it over
+        // three tiles, and the right column gets the one mine spread over all five.
+        assert_eq!(shades, vec![(2, 9), (3, 3), (4, 2), (8, 3), (9, 2)]);
+        assert_eq!(heat_shade(0.0), 0);
+    }
 }
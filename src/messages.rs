@@ -0,0 +1,615 @@
+use std::{env, fmt, str::FromStr};
+
+/// The language of the user interface.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    /// The language selected by the `LANG` environment variable, English if it is unset
+    /// or names a language without a translation.
+    pub fn from_env() -> Self {
+        env::var("LANG").map_or(Language::English, |locale| Language::from_locale(&locale))
+    }
+
+    /// The language of a locale like `de_DE.UTF-8`.
+    pub fn from_locale(locale: &str) -> Self {
+        if locale.starts_with("de") {
+            Language::German
+        } else {
+            Language::English
+        }
+    }
+
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Language::English => &ENGLISH,
+            Language::German => &GERMAN,
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "en" => Ok(Language::English),
+            "de" => Ok(Language::German),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The user facing strings of one language. Every `{}` is replaced by an argument with
+/// [`fill`].
+#[derive(Debug)]
+pub struct Messages {
+    pub parse_error: &'static str,
+    pub row_out_of_range: &'static str,
+    pub col_out_of_range: &'static str,
+    pub index_out_of_range: &'static str,
+    pub read_error: &'static str,
+    pub batch_error: &'static str,
+    pub no_previous_command: &'static str,
+    pub daily: &'static str,
+    pub head_start: &'static str,
+
+    pub won: &'static str,
+    pub lost: &'static str,
+    pub loss_forced_guess: &'static str,
+    pub loss_safe_move: &'static str,
+    pub loss_wrong_flag: &'static str,
+    pub play_again: &'static str,
+    pub status: &'static str,
+    pub progress: &'static str,
+    pub lives: &'static str,
+    pub life_lost: &'static str,
+    pub versus_turn: &'static str,
+    pub versus_winner: &'static str,
+    pub versus_draw: &'static str,
+    pub versus_opened: &'static str,
+    pub time: &'static str,
+    pub hints_used: &'static str,
+    pub assists_used: &'static str,
+    pub wrong_flags: &'static str,
+    pub three_bv: &'static str,
+    pub clicks: &'static str,
+    pub efficiency: &'static str,
+    pub result_playing: &'static str,
+    pub result_won: &'static str,
+    pub result_lost: &'static str,
+    pub replay_result: &'static str,
+    pub replay_json_result: &'static str,
+    pub autoplay_deductions: &'static str,
+    pub autoplay_guesses: &'static str,
+    pub stats_games: &'static str,
+    pub stats_assisted: &'static str,
+    pub stats_times: &'static str,
+
+    pub high_scores: &'static str,
+    pub no_high_scores: &'static str,
+    pub no_scores_yet: &'static str,
+    pub anonymous: &'static str,
+    pub new_high_score: &'static str,
+    pub scores_load_failed: &'static str,
+    pub scores_save_failed: &'static str,
+
+    pub menu_open: &'static str,
+    pub menu_flag: &'static str,
+    pub menu_help: &'static str,
+    pub help_title: &'static str,
+    pub help_open: &'static str,
+    pub help_open_index: &'static str,
+    pub help_flag: &'static str,
+    pub help_chord: &'static str,
+    pub help_info: &'static str,
+    pub help_hint: &'static str,
+    pub help_guess: &'static str,
+    pub help_heatmap: &'static str,
+    pub help_autoflag: &'static str,
+    pub help_assist: &'static str,
+    pub help_continue: &'static str,
+    pub help_undo: &'static str,
+    pub help_save: &'static str,
+    pub help_snapshot: &'static str,
+    pub help_code: &'static str,
+    pub help_restart: &'static str,
+    pub help_repeat: &'static str,
+    pub help_help: &'static str,
+    pub help_exit: &'static str,
+    pub help_batch: &'static str,
+
+    pub saved: &'static str,
+    pub save_failed: &'static str,
+    pub exported: &'static str,
+    pub export_failed: &'static str,
+    pub replay_save_failed: &'static str,
+    pub game_export_failed: &'static str,
+    #[cfg_attr(not(feature = "autosave"), allow(dead_code))]
+    pub autosaved: &'static str,
+    pub board_code: &'static str,
+    pub no_board_code: &'static str,
+    pub board_code_after_first_click: &'static str,
+    pub nothing_to_undo: &'static str,
+    pub nothing_to_continue: &'static str,
+    pub assist: &'static str,
+    pub assist_nothing: &'static str,
+    pub reveal_paused: &'static str,
+    pub undo_in_versus: &'static str,
+    pub hint: &'static str,
+    pub no_hint: &'static str,
+    pub guess_without_mines: &'static str,
+    pub guess: &'static str,
+    pub no_guess: &'static str,
+    pub heatmap: &'static str,
+    pub autoflag_placed: &'static str,
+    pub autoflag_contradiction: &'static str,
+    pub autoflag_undecided: &'static str,
+    pub out_of_flags: &'static str,
+    pub tile_flagged: &'static str,
+    pub info: &'static str,
+    pub info_concealed: &'static str,
+    pub info_flagged: &'static str,
+    pub info_question: &'static str,
+    pub info_open: &'static str,
+    pub info_mine: &'static str,
+    pub info_wrong_flag: &'static str,
+    pub no_solvable_board: &'static str,
+    pub opening: &'static str,
+    pub opening_single_tile: &'static str,
+    pub opening_rated: &'static str,
+    pub rating: &'static str,
+    pub rating_logic_only: &'static str,
+    pub rating_one_guess: &'static str,
+    pub rating_guessy: &'static str,
+
+    pub row: &'static str,
+    pub highlighted: &'static str,
+    pub word_concealed: &'static str,
+    pub word_flagged: &'static str,
+    pub word_question: &'static str,
+    pub word_empty: &'static str,
+    pub word_mine: &'static str,
+    pub word_triggered_mine: &'static str,
+    pub word_wrong_flag: &'static str,
+    pub action_open: &'static str,
+    pub action_flag: &'static str,
+    pub action_hit_mine: &'static str,
+    pub action_question: &'static str,
+    pub action_unmark: &'static str,
+    pub action_chord: &'static str,
+    pub action_continue: &'static str,
+    pub action_undo: &'static str,
+
+    pub tui_help: &'static str,
+    pub tui_won: &'static str,
+    pub tui_lost: &'static str,
+
+    pub config_file_invalid: &'static str,
+    pub missing_value: &'static str,
+    pub invalid_value: &'static str,
+    pub unknown_color: &'static str,
+    pub unknown_argument: &'static str,
+    pub lives_zero: &'static str,
+    pub reveal_budget_zero: &'static str,
+    pub load_with_puzzle: &'static str,
+    pub mines_with_density: &'static str,
+    pub daily_conflict: &'static str,
+    pub tui_conflict: &'static str,
+    pub versus_conflict: &'static str,
+    pub reveal_budget_conflict: &'static str,
+    pub head_start_conflict: &'static str,
+    pub script_conflict: &'static str,
+    pub record_conflict: &'static str,
+    pub accessible_conflict: &'static str,
+    pub compact_conflict: &'static str,
+    pub head_start_too_large: &'static str,
+    pub board_empty: &'static str,
+    pub too_many_mines: &'static str,
+    pub mines_do_not_fit: &'static str,
+    pub board_too_large: &'static str,
+    pub difficulty_override: &'static str,
+}
+
+pub const ENGLISH: Messages = Messages {
+    parse_error: "Failed to parse the input. Please try again",
+    row_out_of_range: "The row {} is out of range, it must be between 0 and {}",
+    col_out_of_range: "The column {} is out of range, it must be between 0 and {}",
+    index_out_of_range: "The tile index {} is out of range, it must be between 0 and {}",
+    read_error: "Failed to read input: {}",
+    batch_error: "Command {} of the line failed, nothing was executed: {}",
+    no_previous_command: "There is no previous command to repeat",
+    daily: "Daily challenge {}, the board center was opened for you",
+    head_start: "The head start opened {} more tiles",
+
+    won: "You won!",
+    lost: "You lost!",
+    loss_forced_guess: "This loss was unavoidable (forced guess)",
+    loss_safe_move: "A safe move existed, e.g. {}",
+    loss_wrong_flag: "The chord relied on the wrong flag at {}",
+    play_again: "Play again? (y/n)",
+    status: "Mines: {}  Flagged: {}  Remaining: {}  Time: {}s",
+    progress: "Opened {}/{} safe tiles",
+    lives: "Lives: {}",
+    life_lost: "You hit a mine and lost a life, {} left. The mine is flagged now.",
+    versus_turn: "Player {} to move  Opened: {} / {}",
+    versus_winner: "Player {} wins!",
+    versus_draw: "Draw, both players opened the same number of tiles!",
+    versus_opened: "Opened tiles: player 1 {}, player 2 {}",
+    time: "Time: {}s",
+    hints_used: "Hints used: {}",
+    assists_used: "Assists used: {}",
+    wrong_flags: "Incorrect flags: {}",
+    three_bv: "3BV: {}",
+    clicks: "Clicks: {}",
+    efficiency: "Efficiency: {}%",
+    result_playing: "Still playing",
+    result_won: "Won",
+    result_lost: "Lost",
+    replay_result: "{} after {} moves",
+    replay_json_result: "{} after {} moves, recorded as {}",
+    autoplay_deductions: "Deductions: {}",
+    autoplay_guesses: "Guesses: {}",
+    stats_games: "Games played: {}  Won: {}  Win rate: {}%",
+    stats_assisted: "Won with the assist: {}  Won unaided: {}",
+    stats_times: "Best time: {}s  Average time: {}s",
+
+    high_scores: "High scores ({}):",
+    no_high_scores: "No high scores yet",
+    no_scores_yet: "No scores yet",
+    anonymous: "Anonymous",
+    new_high_score: "New high score! Enter your name:",
+    scores_load_failed: "Failed to load the high scores: {}",
+    scores_save_failed: "Failed to save the high scores: {}",
+
+    menu_open: "Type 'x {}' to open a tile",
+    menu_flag: "Type 'f {}' to set a flag or question mark",
+    menu_help: "Type 'help' to see all commands",
+    help_title: "Controls (for example '{}'):",
+    help_open: "Open a tile",
+    help_open_index: "Open a tile by its index, counted row by row",
+    help_flag: "Cycle a tile through flag, question mark and concealed",
+    help_chord: "Open the neighbours of a number with all of its flags placed",
+    help_info: "Describe a tile and its neighbours without changing anything",
+    help_hint: "Highlight a safe tile",
+    help_guess: "Highlight the tile least likely to be a mine",
+    help_heatmap: "Shade the tiles next to the numbers by their estimated chance of a mine",
+    help_autoflag: "Flag every tile that is certainly a mine",
+    help_assist: "Open every tile that is certainly safe and flag every certain mine",
+    help_continue: "Open the next tiles of a flood fill paused by --reveal-budget",
+    help_undo: "Revert the last move",
+    help_save: "Save the game to a file",
+    help_snapshot: "Export the board as a PPM image",
+    help_code: "Show the code to share this board",
+    help_restart: "Start a new game",
+    help_repeat: "Repeat the last command",
+    help_help: "Show this help",
+    help_exit: "Exit the game",
+    help_batch: "Separate several commands with ';' to run them in order, e.g. 'x 1 2; f 0 0'",
+
+    saved: "Saved the game to '{}'",
+    save_failed: "Failed to save the game to '{}': {}",
+    exported: "Exported the board to '{}'",
+    export_failed: "Failed to export the board to '{}': {}",
+    replay_save_failed: "Failed to save the replay to '{}': {}",
+    game_export_failed: "Failed to export the game to '{}': {}",
+    autosaved: "Saved the game to '{}', continue it with --load",
+    board_code: "Board code: {}",
+    no_board_code: "This board was not generated in this session and has no code",
+    board_code_after_first_click: "The board code is available after the first click",
+    nothing_to_undo: "Nothing to undo",
+    nothing_to_continue: "There is no paused flood fill to continue",
+    assist: "The assist opened {} tiles and placed {} flags",
+    assist_nothing: "The assist found no tile that is certainly safe or a mine",
+    reveal_paused: "The flood fill is paused, type 'continue' to open more tiles",
+    undo_in_versus: "Moves cannot be undone in a versus game",
+    hint: "Hint: {} is safe",
+    no_hint: "No safe move found — you may have to guess.",
+    guess_without_mines: "No mines are placed yet, any tile is a fine start",
+    guess: "Guess: {} has an estimated {}% chance of being a mine",
+    no_guess: "There is no tile left to guess",
+    heatmap:
+        "Heatmap: the estimated chance of a mine next to the numbers, from 0 (safe) to 9 (certain)",
+    autoflag_placed: "Flagged {} tiles that are certainly mines",
+    autoflag_contradiction: "The flags around {} do not match its number, fix them first",
+    autoflag_undecided: "No concealed tile is certainly a mine yet",
+    out_of_flags: "Out of flags.",
+    tile_flagged: "Tile is flagged; unflag it first.",
+    info: "{} is {}. It has {} neighbours: {} flagged and {} concealed",
+    info_concealed: "concealed",
+    info_flagged: "flagged",
+    info_question: "marked with a question mark",
+    info_open: "open with {} neighbouring mines",
+    info_mine: "a mine",
+    info_wrong_flag: "wrongly flagged",
+    no_solvable_board: "Warning: no board solvable without guessing was found",
+    opening: "Opening: {} tiles",
+    opening_single_tile: "Opening: 1 tile, the first click did not start a flood",
+    opening_rated: "{}, {}",
+    rating: "Difficulty: {}",
+    rating_logic_only: "logic only, no guessing needed",
+    rating_one_guess: "1 guess needed",
+    rating_guessy: "guessy, {} guesses needed",
+
+    row: "Row {}: {}",
+    highlighted: "Highlighted tile: {}",
+    word_concealed: "concealed",
+    word_flagged: "flagged",
+    word_question: "question mark",
+    word_empty: "empty",
+    word_mine: "mine",
+    word_triggered_mine: "exploded mine",
+    word_wrong_flag: "wrong flag",
+    action_open: "Last action: opened {} → revealed {} tiles",
+    action_hit_mine: "Last action: opened {} → hit a mine",
+    action_flag: "Last action: flagged {}",
+    action_question: "Last action: marked {} with a question mark",
+    action_unmark: "Last action: removed the mark from {}",
+    action_chord: "Last action: chorded {} → revealed {} tiles",
+    action_continue: "Last action: continued the flood fill → revealed {} tiles",
+    action_undo: "Last action: undid the last move",
+
+    tui_help: "Arrow keys move, space opens, f flags, c chords, u undoes, r restarts, q quits",
+    tui_won: "You won! Press r to restart or q to quit",
+    tui_lost: "You lost! Press r to restart or q to quit",
+
+    config_file_invalid: "Invalid config file {}: {}",
+    missing_value: "Missing value for '{}'",
+    invalid_value: "Invalid value '{}' for '{}'",
+    unknown_color: "Unknown color '{}' for '{}'",
+    unknown_argument: "Unknown argument '{}'",
+    lives_zero: "'--lives' needs at least one life",
+    reveal_budget_zero: "'--reveal-budget' needs to open at least one tile",
+    load_with_puzzle: "Use either '--load' or '--puzzle', not both",
+    mines_with_density: "Use either '--mines' or '--density', not both",
+    daily_conflict: "'--daily' picks its own board, it does not work with '--seed', '--code', '--load' or '--puzzle'",
+    tui_conflict: "'--tui' does not work with '--daily' or '--code'",
+    versus_conflict: "'--versus' needs the regular interface, not '--tui' or '--autoplay'",
+    reveal_budget_conflict: "'--reveal-budget' does not work with '--tui' or '--versus'",
+    head_start_conflict: "'--head-start' does not work with '--tui', '--versus' or '--reveal-budget'",
+    script_conflict: "'--script' does not work with '--tui' or '--incremental'",
+    record_conflict: "'--record' only works for newly generated games",
+    accessible_conflict: "'--accessible' does not work with '--tui'",
+    compact_conflict: "'--compact' does not work with '--tui', '--accessible' or '--incremental'",
+    head_start_too_large: "'--head-start' can open at most {} tiles on a {}x{} board",
+    board_empty: "The board needs at least one row and column, got {}x{}",
+    too_many_mines: "The mine count {} must be smaller than the number of tiles {}",
+    mines_do_not_fit: "The mine count {} does not fit next to the {} tiles kept free by a first click safe radius of {}",
+    board_too_large: "The board size {}x{} is too large",
+    difficulty_override: "Note: the explicit dimensions override the {} preset",
+};
+
+pub const GERMAN: Messages = Messages {
+    parse_error: "Die Eingabe konnte nicht gelesen werden. Bitte erneut versuchen",
+    row_out_of_range: "Die Zeile {} liegt außerhalb des Spielfelds, sie muss zwischen 0 und {} liegen",
+    col_out_of_range: "Die Spalte {} liegt außerhalb des Spielfelds, sie muss zwischen 0 und {} liegen",
+    index_out_of_range: "Der Feldindex {} liegt außerhalb des Spielfelds, er muss zwischen 0 und {} liegen",
+    read_error: "Die Eingabe konnte nicht gelesen werden: {}",
+    batch_error: "Befehl {} der Zeile ist fehlgeschlagen, nichts wurde ausgeführt: {}",
+    no_previous_command: "Es gibt keinen vorherigen Befehl zum Wiederholen",
+    daily: "Tägliche Herausforderung {}, die Mitte des Spielfelds wurde für dich aufgedeckt",
+    head_start: "Der Vorsprung hat {} weitere Felder aufgedeckt",
+
+    won: "Gewonnen!",
+    lost: "Verloren!",
+    loss_forced_guess: "Diese Niederlage war unvermeidbar (erzwungenes Raten)",
+    loss_safe_move: "Es gab einen sicheren Zug, z. B. {}",
+    loss_wrong_flag: "Die Nachbarn wurden wegen der falschen Flagge auf {} geöffnet",
+    play_again: "Nochmal spielen? (j/n)",
+    status: "Minen: {}  Markiert: {}  Übrig: {}  Zeit: {}s",
+    progress: "{}/{} sichere Felder aufgedeckt",
+    lives: "Leben: {}",
+    life_lost: "Du hast eine Mine getroffen und ein Leben verloren, {} übrig. Die Mine ist jetzt markiert.",
+    versus_turn: "Spieler {} ist am Zug  Aufgedeckt: {} / {}",
+    versus_winner: "Spieler {} gewinnt!",
+    versus_draw: "Unentschieden, beide Spieler haben gleich viele Felder aufgedeckt!",
+    versus_opened: "Aufgedeckte Felder: Spieler 1 {}, Spieler 2 {}",
+    time: "Zeit: {}s",
+    hints_used: "Genutzte Hinweise: {}",
+    assists_used: "Genutzte Assistenzen: {}",
+    wrong_flags: "Falsche Flaggen: {}",
+    three_bv: "3BV: {}",
+    clicks: "Klicks: {}",
+    efficiency: "Effizienz: {}%",
+    result_playing: "Noch im Spiel",
+    result_won: "Gewonnen",
+    result_lost: "Verloren",
+    replay_result: "{} nach {} Zügen",
+    replay_json_result: "{} nach {} Zügen, aufgezeichnet als {}",
+    autoplay_deductions: "Schlussfolgerungen: {}",
+    autoplay_guesses: "Geraten: {}",
+    stats_games: "Gespielt: {}  Gewonnen: {}  Gewinnquote: {}%",
+    stats_assisted: "Mit Assistenz gewonnen: {}  Ohne Hilfe gewonnen: {}",
+    stats_times: "Bestzeit: {}s  Durchschnitt: {}s",
+
+    high_scores: "Bestenliste ({}):",
+    no_high_scores: "Noch keine Bestzeiten",
+    no_scores_yet: "Noch keine Einträge",
+    anonymous: "Anonym",
+    new_high_score: "Neue Bestzeit! Gib deinen Namen ein:",
+    scores_load_failed: "Die Bestenliste konnte nicht geladen werden: {}",
+    scores_save_failed: "Die Bestenliste konnte nicht gespeichert werden: {}",
+
+    menu_open: "'x {}' eingeben, um ein Feld aufzudecken",
+    menu_flag: "'f {}' eingeben, um eine Flagge oder ein Fragezeichen zu setzen",
+    menu_help: "'help' eingeben, um alle Befehle zu sehen",
+    help_title: "Steuerung (zum Beispiel '{}'):",
+    help_open: "Ein Feld aufdecken",
+    help_open_index: "Ein Feld über seinen Index aufdecken, zeilenweise gezählt",
+    help_flag: "Ein Feld zwischen Flagge, Fragezeichen und verdeckt wechseln",
+    help_chord: "Die Nachbarn einer Zahl aufdecken, deren Flaggen alle gesetzt sind",
+    help_info: "Ein Feld und seine Nachbarn beschreiben, ohne etwas zu verändern",
+    help_hint: "Ein sicheres Feld hervorheben",
+    help_guess: "Das Feld hervorheben, das am wenigsten wahrscheinlich eine Mine ist",
+    help_heatmap: "Die Felder neben den Zahlen nach ihrer geschätzten Minenwahrscheinlichkeit einfärben",
+    help_autoflag: "Alle Felder markieren, die sicher Minen sind",
+    help_assist: "Alle sicheren Felder aufdecken und alle sicheren Minen markieren",
+    help_continue: "Die nächsten Felder einer durch --reveal-budget angehaltenen Aufdeckung öffnen",
+    help_undo: "Den letzten Zug zurücknehmen",
+    help_save: "Das Spiel in einer Datei speichern",
+    help_snapshot: "Das Spielfeld als PPM-Bild exportieren",
+    help_code: "Den Code zum Teilen dieses Spielfelds anzeigen",
+    help_restart: "Ein neues Spiel starten",
+    help_repeat: "Den letzten Befehl wiederholen",
+    help_help: "Diese Hilfe anzeigen",
+    help_exit: "Das Spiel beenden",
+    help_batch: "Mehrere Befehle mit ';' trennen, um sie nacheinander auszuführen, z. B. 'x 1 2; f 0 0'",
+
+    saved: "Das Spiel wurde in '{}' gespeichert",
+    save_failed: "Das Spiel konnte nicht in '{}' gespeichert werden: {}",
+    exported: "Das Spielfeld wurde nach '{}' exportiert",
+    export_failed: "Das Spielfeld konnte nicht nach '{}' exportiert werden: {}",
+    replay_save_failed: "Die Aufzeichnung konnte nicht in '{}' gespeichert werden: {}",
+    game_export_failed: "Das Spiel konnte nicht nach '{}' exportiert werden: {}",
+    autosaved: "Das Spiel wurde in '{}' gespeichert, mit --load geht es weiter",
+    board_code: "Spielfeld-Code: {}",
+    no_board_code: "Dieses Spielfeld wurde nicht in dieser Sitzung erzeugt und hat keinen Code",
+    board_code_after_first_click: "Der Spielfeld-Code ist nach dem ersten Klick verfügbar",
+    nothing_to_undo: "Es gibt nichts zurückzunehmen",
+    nothing_to_continue: "Es gibt keine angehaltene Aufdeckung zum Fortsetzen",
+    assist: "Die Assistenz hat {} Felder aufgedeckt und {} Markierungen gesetzt",
+    assist_nothing: "Die Assistenz hat kein sicheres Feld und keine sichere Mine gefunden",
+    reveal_paused: "Die Aufdeckung ist angehalten, 'continue' öffnet weitere Felder",
+    undo_in_versus: "Im Duell können Züge nicht zurückgenommen werden",
+    hint: "Hinweis: {} ist sicher",
+    no_hint: "Kein sicherer Zug gefunden — eventuell musst du raten.",
+    guess_without_mines: "Es sind noch keine Minen gelegt, jedes Feld ist ein guter Anfang",
+    guess: "Tipp: {} ist mit geschätzt {}% Wahrscheinlichkeit eine Mine",
+    no_guess: "Es ist kein Feld mehr zum Raten übrig",
+    heatmap: "Heatmap: die geschätzte Minenwahrscheinlichkeit neben den Zahlen, von 0 (sicher) bis 9 (sicher eine Mine)",
+    autoflag_placed: "{} Felder markiert, die sicher Minen sind",
+    autoflag_contradiction: "Die Flaggen um {} passen nicht zu seiner Zahl, korrigiere sie zuerst",
+    autoflag_undecided: "Noch ist kein verdecktes Feld sicher eine Mine",
+    out_of_flags: "Keine Flaggen mehr übrig.",
+    tile_flagged: "Das Feld ist markiert, entferne zuerst die Flagge.",
+    info: "{} ist {}. Es hat {} Nachbarn: {} markiert und {} verdeckt",
+    info_concealed: "verdeckt",
+    info_flagged: "markiert",
+    info_question: "mit einem Fragezeichen markiert",
+    info_open: "aufgedeckt mit {} benachbarten Minen",
+    info_mine: "eine Mine",
+    info_wrong_flag: "falsch markiert",
+    no_solvable_board: "Warnung: Es wurde kein Spielfeld gefunden, das ohne Raten lösbar ist",
+    opening: "Öffnung: {} Felder",
+    opening_single_tile: "Öffnung: 1 Feld, der erste Klick hat keine Fläche aufgedeckt",
+    opening_rated: "{}, {}",
+    rating: "Schwierigkeit: {}",
+    rating_logic_only: "nur Logik, kein Raten nötig",
+    rating_one_guess: "1 Mal raten nötig",
+    rating_guessy: "glückslastig, {} Mal raten nötig",
+
+    row: "Zeile {}: {}",
+    highlighted: "Hervorgehobenes Feld: {}",
+    word_concealed: "verdeckt",
+    word_flagged: "markiert",
+    word_question: "Fragezeichen",
+    word_empty: "leer",
+    word_mine: "Mine",
+    word_triggered_mine: "explodierte Mine",
+    word_wrong_flag: "falsche Markierung",
+    action_open: "Letzte Aktion: {} aufgedeckt → {} Felder geöffnet",
+    action_hit_mine: "Letzte Aktion: {} aufgedeckt → Mine getroffen",
+    action_flag: "Letzte Aktion: {} markiert",
+    action_question: "Letzte Aktion: {} mit einem Fragezeichen markiert",
+    action_unmark: "Letzte Aktion: Markierung von {} entfernt",
+    action_chord: "Letzte Aktion: Nachbarn von {} geöffnet → {} Felder geöffnet",
+    action_continue: "Letzte Aktion: Aufdecken fortgesetzt → {} Felder geöffnet",
+    action_undo: "Letzte Aktion: letzter Zug zurückgenommen",
+
+    tui_help: "Pfeiltasten bewegen, Leertaste deckt auf, f markiert, c öffnet Nachbarn, u nimmt zurück, r startet neu, q beendet",
+    tui_won: "Gewonnen! r startet neu, q beendet",
+    tui_lost: "Verloren! r startet neu, q beendet",
+
+    config_file_invalid: "Ungültige Konfigurationsdatei {}: {}",
+    missing_value: "Fehlender Wert für '{}'",
+    invalid_value: "Ungültiger Wert '{}' für '{}'",
+    unknown_color: "Unbekannte Farbe '{}' für '{}'",
+    unknown_argument: "Unbekanntes Argument '{}'",
+    lives_zero: "'--lives' braucht mindestens ein Leben",
+    reveal_budget_zero: "'--reveal-budget' muss mindestens ein Feld aufdecken",
+    load_with_puzzle: "Entweder '--load' oder '--puzzle' verwenden, nicht beides",
+    mines_with_density: "Entweder '--mines' oder '--density' verwenden, nicht beides",
+    daily_conflict: "'--daily' wählt sein eigenes Spielfeld und funktioniert nicht mit '--seed', '--code', '--load' oder '--puzzle'",
+    tui_conflict: "'--tui' funktioniert nicht mit '--daily' oder '--code'",
+    versus_conflict: "'--versus' braucht die normale Oberfläche, nicht '--tui' oder '--autoplay'",
+    reveal_budget_conflict: "'--reveal-budget' funktioniert nicht mit '--tui' oder '--versus'",
+    head_start_conflict: "'--head-start' funktioniert nicht mit '--tui', '--versus' oder '--reveal-budget'",
+    script_conflict: "'--script' funktioniert nicht mit '--tui' oder '--incremental'",
+    record_conflict: "'--record' funktioniert nur für neu erzeugte Spiele",
+    accessible_conflict: "'--accessible' funktioniert nicht mit '--tui'",
+    compact_conflict: "'--compact' funktioniert nicht mit '--tui', '--accessible' oder '--incremental'",
+    head_start_too_large: "'--head-start' kann höchstens {} Felder auf einem {}x{}-Spielfeld aufdecken",
+    board_empty: "Das Spielfeld braucht mindestens eine Zeile und Spalte, erhalten {}x{}",
+    too_many_mines: "Die Minenanzahl {} muss kleiner als die Anzahl der Felder {} sein",
+    mines_do_not_fit: "Die Minenanzahl {} passt nicht neben die {} Felder, die ein sicherer Radius von {} um den ersten Klick frei hält",
+    board_too_large: "Die Spielfeldgröße {}x{} ist zu groß",
+    difficulty_override: "Hinweis: Die angegebenen Maße ersetzen die Vorgabe {}",
+};
+
+/// Replaces the `{}` placeholders of the template with the arguments in order.
+pub fn fill(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut parts = template.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+
+    for (idx, part) in parts.enumerate() {
+        if let Some(arg) = args.get(idx) {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn languages_are_selected_by_their_code() {
+        assert_eq!("en".parse(), Ok(Language::English));
+        assert_eq!("de".parse(), Ok(Language::German));
+        assert_eq!("fr".parse::<Language>(), Err(()));
+    }
+
+    #[test]
+    fn languages_are_selected_by_the_locale() {
+        assert_eq!(Language::from_locale("de_DE.UTF-8"), Language::German);
+        assert_eq!(Language::from_locale("de"), Language::German);
+        assert_eq!(Language::from_locale("en_GB.UTF-8"), Language::English);
+        assert_eq!(Language::from_locale("fr_FR.UTF-8"), Language::English);
+        assert_eq!(Language::from_locale("C"), Language::English);
+    }
+
+    #[test]
+    fn every_language_has_its_own_strings() {
+        assert_eq!(Language::English.messages().won, "You won!");
+        assert_eq!(Language::German.messages().won, "Gewonnen!");
+        assert_eq!(
+            fill(Language::English.messages().replay_result, &[&"Won", &12]),
+            "Won after 12 moves"
+        );
+        assert_eq!(
+            fill(
+                Language::German.messages().replay_result,
+                &[&"Gewonnen", &12]
+            ),
+            "Gewonnen nach 12 Zügen"
+        );
+        assert_eq!(
+            fill(Language::German.messages().high_scores, &[&"beginner"]),
+            "Bestenliste (beginner):"
+        );
+    }
+
+    #[test]
+    fn fill_replaces_the_placeholders_in_order() {
+        assert_eq!(fill("{} and {}", &[&1, &"two"]), "1 and two");
+        assert_eq!(fill("{} and {}", &[&1]), "1 and ");
+        assert_eq!(fill("no placeholder", &[&1]), "no placeholder");
+    }
+}
@@ -0,0 +1,381 @@
+use std::{fmt::Write as _, fs, time::Instant};
+
+use cli_minesweeper::{Connectivity, Coord, Error, GameState};
+
+use crate::{config::Config, InputAction};
+
+/// The settings a recorded game was generated and played with. Together with the seed
+/// they make replaying the moves deterministic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReplaySettings {
+    pub seed: u64,
+    pub row_count: usize,
+    pub col_count: usize,
+    pub mine_count: usize,
+    pub first_click_safe: usize,
+    pub wrap: bool,
+    pub connectivity: Connectivity,
+    pub no_guess: bool,
+    pub easy_start: bool,
+    pub question_marks: bool,
+    pub strict_flags: bool,
+    pub lives: usize,
+    pub reveal_budget: Option<usize>,
+    pub versus: bool,
+}
+
+impl ReplaySettings {
+    pub fn new(state: &GameState, config: &Config, seed: u64) -> Self {
+        ReplaySettings {
+            seed,
+            row_count: state.board.row_count(),
+            col_count: state.board.col_count(),
+            mine_count: state.mine_count,
+            first_click_safe: config.first_click_safe,
+            wrap: state.board.wraps(),
+            connectivity: state.board.connectivity(),
+            no_guess: config.no_guess,
+            easy_start: config.easy_start,
+            question_marks: config.question_marks,
+            strict_flags: config.strict_flags,
+            lives: state.max_lives(),
+            reveal_budget: config.reveal_budget,
+            versus: state.versus().is_some(),
+        }
+    }
+
+    pub fn apply(&self, config: &mut Config) {
+        config.seed = Some(self.seed);
+        config.row_count = self.row_count;
+        config.col_count = self.col_count;
+        config.mine_count = self.mine_count;
+        config.first_click_safe = self.first_click_safe;
+        config.wrap = self.wrap;
+        config.connectivity = self.connectivity;
+        config.no_guess = self.no_guess;
+        config.easy_start = self.easy_start;
+        config.question_marks = self.question_marks;
+        config.strict_flags = self.strict_flags;
+        config.lives = self.lives;
+        config.reveal_budget = self.reveal_budget;
+        config.versus = self.versus;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Move {
+    Open(Coord),
+    Flag(Coord),
+    Chord(Coord),
+    Undo,
+    Autoflag,
+    Assist,
+    Continue,
+}
+
+/// A recorded game: the settings header followed by every move and the number of
+/// seconds since the start of the game it was made at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+    pub settings: ReplaySettings,
+    pub moves: Vec<(f64, Move)>,
+}
+
+/// Collects the moves of the current game until it is written on game over.
+#[derive(Debug)]
+pub struct Recorder {
+    recording: Recording,
+    start_time: Instant,
+}
+
+impl Recorder {
+    pub fn new(settings: ReplaySettings) -> Self {
+        Recorder {
+            recording: Recording {
+                settings,
+                moves: Vec::new(),
+            },
+            start_time: Instant::now(),
+        }
+    }
+
+    /// Records the moves that change the board, every other action is ignored.
+    pub fn record(&mut self, action: &InputAction) {
+        let col_count = self.recording.settings.col_count;
+
+        let recorded_move = match *action {
+            InputAction::Open(idx) => Move::Open(Coord::from_index(idx, col_count)),
+            InputAction::Flag(idx) => Move::Flag(Coord::from_index(idx, col_count)),
+            InputAction::Chord(idx) => Move::Chord(Coord::from_index(idx, col_count)),
+            InputAction::Undo => Move::Undo,
+            InputAction::Autoflag => Move::Autoflag,
+            InputAction::Assist => Move::Assist,
+            InputAction::Continue => Move::Continue,
+            _ => return,
+        };
+
+        let seconds = self.start_time.elapsed().as_secs_f64();
+        self.recording.moves.push((seconds, recorded_move));
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let settings = &self.recording.settings;
+
+        let mut replay = format!(
+            "seed={} rows={} cols={} mines={} first_click_safe={} wrap={} no_guess={} easy_start={} question_marks={} strict_flags={} lives={}",
+            settings.seed,
+            settings.row_count,
+            settings.col_count,
+            settings.mine_count,
+            settings.first_click_safe,
+            settings.wrap,
+            settings.no_guess,
+            settings.easy_start,
+            settings.question_marks,
+            settings.strict_flags,
+            settings.lives
+        );
+        if settings.connectivity != Connectivity::Eight {
+            let _ = write!(
+                replay,
+                " connectivity={}",
+                settings.connectivity.neighbour_count()
+            );
+        }
+        if let Some(reveal_budget) = settings.reveal_budget {
+            let _ = write!(replay, " reveal_budget={}", reveal_budget);
+        }
+        if settings.versus {
+            replay.push_str(" versus=true");
+        }
+        replay.push('\n');
+
+        for (seconds, recorded_move) in &self.recording.moves {
+            let _ = match recorded_move {
+                Move::Open(coord) => {
+                    writeln!(replay, "t={:.2} open {} {}", seconds, coord.row, coord.col)
+                }
+                Move::Flag(coord) => {
+                    writeln!(replay, "t={:.2} flag {} {}", seconds, coord.row, coord.col)
+                }
+                Move::Chord(coord) => {
+                    writeln!(replay, "t={:.2} chord {} {}", seconds, coord.row, coord.col)
+                }
+                Move::Undo => writeln!(replay, "t={:.2} undo", seconds),
+                Move::Autoflag => writeln!(replay, "t={:.2} autoflag", seconds),
+                Move::Assist => writeln!(replay, "t={:.2} assist", seconds),
+                Move::Continue => writeln!(replay, "t={:.2} continue", seconds),
+            };
+        }
+
+        fs::write(path, replay)?;
+        Ok(())
+    }
+}
+
+pub fn load(path: &str) -> Result<Recording, Error> {
+    let replay = fs::read_to_string(path)?;
+    let mut lines = replay.lines().enumerate();
+
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| Error::Parse("The replay is empty".to_string()))?;
+    let settings = parse_header(header)?;
+
+    let mut moves = Vec::new();
+    for (line_idx, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let invalid = || Error::Parse(format!("Invalid move on line {}: '{}'", line_idx + 1, line));
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+
+        let seconds = parts[0]
+            .strip_prefix("t=")
+            .and_then(|seconds| seconds.parse::<f64>().ok())
+            .ok_or_else(invalid)?;
+
+        let coord = || -> Result<Coord, Error> {
+            match parts[1..] {
+                [_, row, col] => {
+                    let row = row.parse::<usize>().map_err(|_| invalid())?;
+                    let col = col.parse::<usize>().map_err(|_| invalid())?;
+
+                    if row >= settings.row_count || col >= settings.col_count {
+                        return Err(invalid());
+                    }
+
+                    Ok(Coord::new(row, col))
+                }
+                _ => Err(invalid()),
+            }
+        };
+
+        let recorded_move = match parts.get(1) {
+            Some(&"open") => Move::Open(coord()?),
+            Some(&"flag") => Move::Flag(coord()?),
+            Some(&"chord") => Move::Chord(coord()?),
+            Some(&"undo") if parts.len() == 2 => Move::Undo,
+            Some(&"autoflag") if parts.len() == 2 => Move::Autoflag,
+            Some(&"assist") if parts.len() == 2 => Move::Assist,
+            Some(&"continue") if parts.len() == 2 => Move::Continue,
+            _ => return Err(invalid()),
+        };
+
+        moves.push((seconds, recorded_move));
+    }
+
+    Ok(Recording { settings, moves })
+}
+
+fn parse_header(header: &str) -> Result<ReplaySettings, Error> {
+    let mut settings = ReplaySettings {
+        seed: 0,
+        row_count: 0,
+        col_count: 0,
+        mine_count: 0,
+        first_click_safe: 1,
+        wrap: false,
+        connectivity: Connectivity::Eight,
+        no_guess: false,
+        easy_start: false,
+        question_marks: true,
+        strict_flags: false,
+        lives: 1,
+        reveal_budget: None,
+        versus: false,
+    };
+    let mut has_seed = false;
+
+    for field in header.split_whitespace() {
+        let invalid = || Error::Parse(format!("Invalid replay header field '{}'", field));
+        let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+
+        match key {
+            "seed" => {
+                settings.seed = value.parse().map_err(|_| invalid())?;
+                has_seed = true;
+            }
+            "rows" => settings.row_count = value.parse().map_err(|_| invalid())?,
+            "cols" => settings.col_count = value.parse().map_err(|_| invalid())?,
+            "mines" => settings.mine_count = value.parse().map_err(|_| invalid())?,
+            "first_click_safe" => {
+                settings.first_click_safe = value.parse().map_err(|_| invalid())?
+            }
+            "wrap" => settings.wrap = value.parse().map_err(|_| invalid())?,
+            "connectivity" => {
+                settings.connectivity = value
+                    .parse()
+                    .ok()
+                    .and_then(Connectivity::from_neighbour_count)
+                    .ok_or_else(invalid)?
+            }
+            "no_guess" => settings.no_guess = value.parse().map_err(|_| invalid())?,
+            "easy_start" => settings.easy_start = value.parse().map_err(|_| invalid())?,
+            "question_marks" => settings.question_marks = value.parse().map_err(|_| invalid())?,
+            "strict_flags" => settings.strict_flags = value.parse().map_err(|_| invalid())?,
+            "lives" => settings.lives = value.parse().map_err(|_| invalid())?,
+            "reveal_budget" => settings.reveal_budget = Some(value.parse().map_err(|_| invalid())?),
+            "versus" => settings.versus = value.parse().map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        }
+    }
+
+    if !has_seed {
+        return Err(Error::Parse(
+            "The replay header does not contain the seed".to_string(),
+        ));
+    }
+
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::temp_file::TempFile;
+
+    #[test]
+    fn replaying_a_recording_rebuilds_the_final_board() {
+        let config = Config {
+            row_count: 9,
+            col_count: 9,
+            mine_count: 10,
+            connectivity: Connectivity::Four,
+            lives: 2,
+            ..Config::default()
+        };
+        let mut state = GameState::new(config.row_count, config.col_count, config.mine_count);
+        state.board.set_connectivity(config.connectivity);
+        state.set_lives(config.lives);
+        let mut rng = StdRng::seed_from_u64(59);
+        let mut recorder = Recorder::new(ReplaySettings::new(&state, &config, 59));
+
+        for action in [
+            InputAction::Open(40),
+            InputAction::Flag(0),
+            InputAction::Flag(1),
+            InputAction::Undo,
+            InputAction::Assist,
+            InputAction::Open(80),
+            InputAction::Chord(40),
+            InputAction::Open(8),
+        ] {
+            recorder.record(&action);
+            match action {
+                InputAction::Open(idx) => {
+                    crate::open_tile(&mut state, idx, &config, &mut rng, |_| {});
+                }
+                InputAction::Flag(idx) => {
+                    crate::flag_tile(&mut state, idx, &config);
+                }
+                InputAction::Chord(idx) => state.chord(idx),
+                InputAction::Undo => {
+                    state.undo();
+                }
+                InputAction::Assist => {
+                    state.assist();
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        let file = TempFile::new("txt");
+        recorder.save(file.path()).unwrap();
+        let recording = load(file.path()).unwrap();
+        assert_eq!(recording.settings, recorder.recording().settings);
+
+        let mut replayed_config = Config::default();
+        recording.settings.apply(&mut replayed_config);
+        let mut replayed = GameState::new(
+            replayed_config.row_count,
+            replayed_config.col_count,
+            replayed_config.mine_count,
+        );
+        replayed
+            .board
+            .set_connectivity(replayed_config.connectivity);
+        replayed.set_lives(replayed_config.lives);
+        let mut rng = StdRng::seed_from_u64(recording.settings.seed);
+        crate::replay_moves(
+            &mut replayed,
+            recording
+                .moves
+                .iter()
+                .map(|&(_, recorded_move)| recorded_move),
+            &replayed_config,
+            &mut rng,
+        );
+
+        assert_eq!(replayed.board, state.board);
+        assert_eq!(replayed.status, state.status);
+        assert_eq!(replayed.lives, state.lives);
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::PathBuf,
+};
+
+use cli_minesweeper::Error;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Difficulty,
+    messages::{fill, Messages},
+};
+
+const MAX_ENTRIES: usize = 10;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Score {
+    pub seconds: u64,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    tables: BTreeMap<String, Vec<Score>>,
+}
+
+impl HighScores {
+    pub fn load() -> Result<Self, Error> {
+        let file = match File::open(scores_path()) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok(HighScores::default())
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let path = scores_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+
+        Ok(())
+    }
+
+    pub fn qualifies(&self, label: &str, seconds: u64) -> bool {
+        match self.tables.get(label) {
+            Some(scores) => {
+                scores.len() < MAX_ENTRIES || scores.iter().any(|score| seconds < score.seconds)
+            }
+            None => true,
+        }
+    }
+
+    pub fn insert(&mut self, label: &str, score: Score) {
+        let scores = self.tables.entry(label.to_string()).or_default();
+
+        let position = scores.partition_point(|other| other.seconds <= score.seconds);
+        scores.insert(position, score);
+        scores.truncate(MAX_ENTRIES);
+    }
+
+    pub fn print_table(&self, label: &str, messages: &Messages) {
+        println!("{}", fill(messages.high_scores, &[&label]));
+
+        let scores = self.tables.get(label).map_or(&[][..], |scores| scores);
+        if scores.is_empty() {
+            println!("  {}", messages.no_scores_yet);
+        }
+
+        for (rank, score) in scores.iter().enumerate() {
+            println!(
+                "  {:>2}. {:>5}s  {}",
+                rank + 1,
+                score.seconds,
+                score.name.as_deref().unwrap_or(messages.anonymous)
+            );
+        }
+    }
+
+    pub fn print(&self, messages: &Messages) {
+        if self.tables.is_empty() {
+            println!("{}", messages.no_high_scores);
+        }
+
+        for label in self.tables.keys() {
+            self.print_table(label, messages);
+            println!();
+        }
+    }
+}
+
+pub fn board_label(row_count: usize, col_count: usize, mine_count: usize) -> String {
+    Difficulty::ALL
+        .into_iter()
+        .find(|difficulty| difficulty.dimensions() == (row_count, col_count, mine_count))
+        .map_or(
+            format!(
+                "custom {}x{} with {} mines",
+                row_count, col_count, mine_count
+            ),
+            |difficulty| difficulty.label().to_string(),
+        )
+}
+
+fn scores_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_default()
+        .join("cli-minesweeper")
+        .join("scores.json")
+}
@@ -0,0 +1,182 @@
+use std::{fmt, str::FromStr};
+
+use cli_minesweeper::{Connectivity, Error, GameState};
+
+use crate::config::Config;
+
+/// Everything that goes into generating a board, so the same board can be played again
+/// from a short code. The mines are placed from the seed after the first click, which is
+/// why the code contains the first click as well.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoardCode {
+    pub row_count: usize,
+    pub col_count: usize,
+    pub mine_count: usize,
+    pub first_click_safe: usize,
+    pub wrap: bool,
+    pub connectivity: Connectivity,
+    pub no_guess: bool,
+    pub easy_start: bool,
+    pub seed: u64,
+    pub first_click: usize,
+}
+
+impl BoardCode {
+    pub fn new(state: &GameState, config: &Config, seed: u64, first_click: usize) -> Self {
+        BoardCode {
+            row_count: state.board.row_count(),
+            col_count: state.board.col_count(),
+            mine_count: state.mine_count,
+            first_click_safe: config.first_click_safe,
+            wrap: state.board.wraps(),
+            connectivity: state.board.connectivity(),
+            no_guess: config.no_guess,
+            easy_start: config.easy_start,
+            seed,
+            first_click,
+        }
+    }
+
+    pub fn apply(&self, config: &mut Config) {
+        config.row_count = self.row_count;
+        config.col_count = self.col_count;
+        config.mine_count = self.mine_count;
+        config.first_click_safe = self.first_click_safe;
+        config.wrap = self.wrap;
+        config.connectivity = self.connectivity;
+        config.no_guess = self.no_guess;
+        config.easy_start = self.easy_start;
+        config.seed = Some(self.seed);
+    }
+}
+
+impl fmt::Display for BoardCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = self.wrap as u64
+            | (self.no_guess as u64) << 1
+            | (self.easy_start as u64) << 2
+            | ((self.connectivity == Connectivity::Four) as u64) << 3;
+
+        let parts = [
+            self.row_count as u64,
+            self.col_count as u64,
+            self.mine_count as u64,
+            self.first_click_safe as u64,
+            flags,
+            self.first_click as u64,
+            self.seed,
+        ];
+
+        let code = parts.map(to_base36).join("-");
+        write!(f, "{}", code)
+    }
+}
+
+impl FromStr for BoardCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Parse(format!("Invalid board code '{}'", s));
+
+        let parts = s
+            .trim()
+            .split('-')
+            .map(|part| u64::from_str_radix(part, 36).map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let [row_count, col_count, mine_count, first_click_safe, flags, first_click, seed] =
+            parts[..]
+        else {
+            return Err(invalid());
+        };
+
+        let code = BoardCode {
+            row_count: row_count as usize,
+            col_count: col_count as usize,
+            mine_count: mine_count as usize,
+            first_click_safe: first_click_safe as usize,
+            wrap: flags & 1 != 0,
+            connectivity: if flags & 8 != 0 {
+                Connectivity::Four
+            } else {
+                Connectivity::Eight
+            },
+            no_guess: flags & 2 != 0,
+            easy_start: flags & 4 != 0,
+            seed,
+            first_click: first_click as usize,
+        };
+
+        let tile_count = code.row_count.checked_mul(code.col_count);
+        if tile_count.is_none_or(|tile_count| code.first_click >= tile_count) {
+            return Err(invalid());
+        }
+
+        Ok(code)
+    }
+}
+
+fn to_base36(mut value: u64) -> String {
+    let mut digits = Vec::new();
+
+    loop {
+        digits.push(std::char::from_digit((value % 36) as u32, 36).unwrap_or('0'));
+        value /= 36;
+
+        if value == 0 {
+            break;
+        }
+    }
+
+    digits.iter().rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn first_open(config: &Config, first_click: usize) -> GameState {
+        let mut state = GameState::new(config.row_count, config.col_count, config.mine_count);
+        state.board.set_wrap(config.wrap);
+        state.board.set_connectivity(config.connectivity);
+
+        let mut rng = StdRng::seed_from_u64(config.seed.unwrap_or_default());
+        crate::open_tile(&mut state, first_click, config, &mut rng, |_| {});
+        state
+    }
+
+    #[test]
+    fn board_code_rebuilds_the_same_board() {
+        let config = Config {
+            row_count: 12,
+            col_count: 9,
+            mine_count: 20,
+            seed: Some(5555),
+            wrap: true,
+            connectivity: Connectivity::Four,
+            easy_start: true,
+            ..Config::default()
+        };
+        let state = first_open(&config, 50);
+
+        let code = BoardCode::new(&state, &config, 5555, 50).to_string();
+        let decoded: BoardCode = code.parse().unwrap();
+        assert_eq!(decoded, BoardCode::new(&state, &config, 5555, 50));
+
+        let mut replayed = Config::default();
+        decoded.apply(&mut replayed);
+        let replayed_state = first_open(&replayed, decoded.first_click);
+
+        assert_eq!(replayed_state.board, state.board);
+    }
+
+    #[test]
+    fn malformed_board_codes_do_not_parse() {
+        assert!("".parse::<BoardCode>().is_err());
+        assert!("a-b-c".parse::<BoardCode>().is_err());
+        // The first click lies outside of the 2x2 board.
+        assert!("2-2-1-1-0-4-0".parse::<BoardCode>().is_err());
+    }
+}
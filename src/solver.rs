@@ -0,0 +1,402 @@
+use std::collections::BTreeSet;
+
+use rand::Rng;
+
+use crate::{Board, OpenResult, Tile};
+
+/// Tiles that follow from the open numbers on the board using the basic single-tile
+/// rules: a number whose flags account for all of its mines makes its remaining
+/// neighbours safe, and a number with exactly as many unknown neighbours as missing
+/// mines makes all of them mines. Flags on the board are trusted to be correct.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Deductions {
+    pub safe: BTreeSet<usize>,
+    pub mines: BTreeSet<usize>,
+}
+
+impl Deductions {
+    pub fn is_empty(&self) -> bool {
+        self.safe.is_empty() && self.mines.is_empty()
+    }
+}
+
+pub fn find_deductions(board: &Board) -> Deductions {
+    let mut deductions = Deductions::default();
+
+    for (idx, tile) in board.tiles().iter().enumerate() {
+        let Tile::Open(mine_count) = *tile else {
+            continue;
+        };
+
+        let summary = board.neighbour_summary(idx);
+        if summary.concealed == 0 {
+            continue;
+        }
+
+        let unknown = || {
+            board
+                .neighbours(idx)
+                .into_iter()
+                .filter(|&idx| matches!(board.tiles()[idx], Tile::Concealed | Tile::Question))
+        };
+
+        if summary.flagged == mine_count {
+            deductions.safe.extend(unknown());
+        } else if summary.flagged + summary.concealed == mine_count {
+            deductions.mines.extend(unknown());
+        }
+    }
+
+    deductions
+}
+
+/// Like [`find_deductions`], but ignores the flags on the board, since the player may
+/// have misplaced one: the mines follow from the open numbers alone, pass after pass
+/// until no new one does, and the safe tiles from the open numbers and those mines.
+pub fn certain_deductions(board: &Board) -> Deductions {
+    let mut board = board.clone();
+    for idx in 0..board.tiles().len() {
+        if board.tiles()[idx] == Tile::Flagged {
+            board.restore_tile(idx, Tile::Concealed);
+        }
+    }
+
+    let mut mines = BTreeSet::new();
+    loop {
+        let deductions = find_deductions(&board);
+        if deductions.mines.is_empty() {
+            return Deductions {
+                safe: deductions.safe,
+                mines,
+            };
+        }
+
+        for idx in deductions.mines {
+            board.place_flag(idx);
+            mines.insert(idx);
+        }
+    }
+}
+
+/// Why [`certain_mines`] found nothing to flag.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Uncertainty {
+    /// The flags around the open number at this index do not match it, so the flags on
+    /// the board cannot be trusted.
+    Contradiction(usize),
+    /// Some concealed tiles may or may not be mines.
+    Undecided,
+}
+
+/// The concealed tiles that must be mines: all of them once their count equals the
+/// number of mines not yet flagged, otherwise the mines forced by [`find_deductions`].
+pub fn certain_mines(board: &Board, mine_count: usize) -> Result<BTreeSet<usize>, Uncertainty> {
+    let tiles = board.tiles();
+
+    for (idx, tile) in tiles.iter().enumerate() {
+        let Tile::Open(number) = *tile else {
+            continue;
+        };
+
+        let neighbours = board.neighbours(idx);
+        let flag_count = neighbours
+            .iter()
+            .filter(|&&idx| tiles[idx] == Tile::Flagged)
+            .count();
+        let unknown_count = neighbours
+            .iter()
+            .filter(|&&idx| matches!(tiles[idx], Tile::Concealed | Tile::Question))
+            .count();
+
+        if flag_count > number || flag_count + unknown_count < number {
+            return Err(Uncertainty::Contradiction(idx));
+        }
+    }
+
+    let unknown = (0..tiles.len())
+        .filter(|&idx| matches!(tiles[idx], Tile::Concealed | Tile::Question))
+        .collect::<BTreeSet<_>>();
+
+    if unknown.len() == mine_count.saturating_sub(board.count_flags()) {
+        return Ok(unknown);
+    }
+
+    let mines = find_deductions(board).mines;
+    if mines.is_empty() {
+        return Err(Uncertainty::Undecided);
+    }
+
+    Ok(mines)
+}
+
+/// Plays the board from the given first click using only [`find_deductions`] and
+/// reports whether every safe tile could be opened without guessing.
+pub fn is_solvable(board: &Board, first_click: usize) -> bool {
+    let mut board = board.clone();
+    board.reveal(first_click);
+
+    loop {
+        let deductions = find_deductions(&board);
+        if deductions.is_empty() {
+            return board.is_won();
+        }
+
+        for idx in deductions.mines {
+            board.flag(idx, false);
+        }
+
+        for idx in deductions.safe {
+            // A wrong flag placed by the player can make a mine look safe.
+            if board.is_mine(idx) {
+                return false;
+            }
+            board.reveal(idx);
+        }
+    }
+}
+
+/// How much guessing a board needs, see [`rate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rating {
+    /// Every safe tile follows from the first click.
+    LogicOnly,
+    OneGuess,
+    /// The solver got stuck this many times, at least twice.
+    Guessy(usize),
+}
+
+/// Solves the board from the given first click with [`find_deductions`] and counts how
+/// often the solver gets stuck. Knowing the mines, a stuck solver guesses right: it
+/// opens a safe tile, preferably one next to the open area, and carries on. The first
+/// click must be safe.
+pub fn rate(board: &Board, first_click: usize) -> Rating {
+    let mut board = board.clone();
+    // The deductions trust the flags, and a wrong one would make a mine look safe.
+    for idx in 0..board.tiles().len() {
+        if board.tiles()[idx] == Tile::Flagged {
+            board.restore_tile(idx, Tile::Concealed);
+        }
+    }
+    board.reveal(first_click);
+    let mut guesses = 0;
+
+    loop {
+        let deductions = find_deductions(&board);
+
+        if deductions.is_empty() {
+            let Some(guess) = lucky_guess(&board) else {
+                break;
+            };
+
+            guesses += 1;
+            board.reveal(guess);
+            continue;
+        }
+
+        for idx in deductions.mines {
+            board.flag(idx, false);
+        }
+
+        for idx in deductions.safe {
+            board.reveal(idx);
+        }
+    }
+
+    match guesses {
+        0 => Rating::LogicOnly,
+        1 => Rating::OneGuess,
+        guesses => Rating::Guessy(guesses),
+    }
+}
+
+/// A concealed safe tile, on the [`Board::frontier`] if there is one.
+fn lucky_guess(board: &Board) -> Option<usize> {
+    board
+        .frontier()
+        .into_iter()
+        .find(|&idx| !board.is_mine(idx))
+        .or_else(|| {
+            (0..board.tiles().len()).find(|&idx| {
+                matches!(board.tiles()[idx], Tile::Concealed | Tile::Question)
+                    && !board.is_mine(idx)
+            })
+        })
+}
+
+/// Places mines until the board can be solved from the first click without guessing.
+/// Returns `false` if no such layout was found within `max_attempts`, in which case the
+/// last generated layout is kept.
+pub fn generate_no_guess_mines(
+    board: &mut Board,
+    mine_count: usize,
+    first_click: usize,
+    safe_radius: usize,
+    max_attempts: usize,
+    rng: &mut impl Rng,
+) -> bool {
+    for attempt in 1..=max_attempts {
+        board.generate_mines(mine_count, first_click, safe_radius, rng);
+
+        if !board.is_mine(first_click) && is_solvable(board, first_click) {
+            log::info!(
+                "Found a board solvable without guessing in {} attempts",
+                attempt
+            );
+            return true;
+        }
+    }
+
+    log::warn!(
+        "No board solvable without guessing found in {} attempts",
+        max_attempts
+    );
+    false
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AutoplayReport {
+    pub won: bool,
+    pub deductions: usize,
+    pub guesses: usize,
+}
+
+/// Plays the board from the given first click without any human input, applying
+/// [`find_deductions`] as moves and guessing the least likely mine whenever no
+/// deduction is left. The board must already have its mines placed.
+pub fn autoplay(board: &mut Board, first_click: usize) -> AutoplayReport {
+    let mut report = AutoplayReport::default();
+    let mut result = board.open(first_click);
+
+    while result == OpenResult::Revealed && !board.is_won() {
+        let deductions = find_deductions(board);
+
+        if deductions.is_empty() {
+            let Some((guess, _)) = suggest_guess(board) else {
+                break;
+            };
+
+            report.guesses += 1;
+            result = board.open(guess);
+            continue;
+        }
+
+        report.deductions += deductions.safe.len() + deductions.mines.len();
+
+        for idx in deductions.mines {
+            board.flag(idx, false);
+        }
+
+        for idx in deductions.safe {
+            result = board.open(idx);
+            if result == OpenResult::HitMine {
+                break;
+            }
+        }
+    }
+
+    report.won = result == OpenResult::Revealed && board.is_won();
+    report
+}
+
+/// Picks the unknown tile with the lowest estimated chance of being a mine, together
+/// with that chance, see [`mine_chances`]. A tile that is certainly safe comes first,
+/// since the estimate can rate it worse than a real guess.
+pub fn suggest_guess(board: &Board) -> Option<(usize, f64)> {
+    if let Some(&idx) = certain_deductions(board).safe.first() {
+        return Some((idx, 0.0));
+    }
+
+    mine_chances(board)
+        .into_iter()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+/// Estimates the chance of every unknown tile being a mine, in ascending order of the
+/// tiles. Every open number spreads its missing mines evenly over its unknown
+/// neighbours, a tile next to several numbers takes the highest chance any of them
+/// implies, and every other tile the chance of the remaining mines spread over all
+/// unknown tiles.
+///
+/// This is a heuristic rather than an exact probability: overlapping numbers are not
+/// solved together, so tiles whose fate follows from a combination of numbers can get
+/// misleading estimates.
+pub fn mine_chances(board: &Board) -> Vec<(usize, f64)> {
+    let is_unknown = |idx: usize| matches!(board.tiles()[idx], Tile::Concealed | Tile::Question);
+
+    let unknown = (0..board.tiles().len())
+        .filter(|&idx| is_unknown(idx))
+        .collect::<Vec<_>>();
+    let remaining_mines = board.mine_count().saturating_sub(board.count_flags());
+    let default_chance = remaining_mines as f64 / unknown.len().max(1) as f64;
+
+    let mut chances: Vec<Option<f64>> = vec![None; board.tiles().len()];
+    for (idx, tile) in board.tiles().iter().enumerate() {
+        let Tile::Open(mine_count) = *tile else {
+            continue;
+        };
+
+        let summary = board.neighbour_summary(idx);
+        if summary.concealed == 0 {
+            continue;
+        }
+
+        let chance = mine_count.saturating_sub(summary.flagged) as f64 / summary.concealed as f64;
+        for neighbour in board
+            .neighbours(idx)
+            .into_iter()
+            .filter(|&idx| is_unknown(idx))
+        {
+            chances[neighbour] = Some(chances[neighbour].map_or(chance, |c| c.max(chance)));
+        }
+    }
+
+    unknown
+        .into_iter()
+        .map(|idx| (idx, chances[idx].unwrap_or(default_chance)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_needs_a_guess_for_a_fifty_fifty() {
+        // The open area ends in two ones that share the same two tiles, and either of
+        // them can hold the one mine.
+        let board: Board = "###*\n####".parse().unwrap();
+
+        assert!(!is_solvable(&board, 0));
+        assert_eq!(rate(&board, 0), Rating::OneGuess);
+    }
+
+    #[test]
+    fn rate_needs_no_guess_when_the_numbers_decide() {
+        let board: Board = "##*\n###\n###".parse().unwrap();
+
+        assert!(is_solvable(&board, 6));
+        assert_eq!(rate(&board, 6), Rating::LogicOnly);
+    }
+
+    #[test]
+    fn rate_ignores_wrong_flags() {
+        // The wrong flag makes the mine next to it look safe, while without it the last
+        // two tiles are a fifty-fifty.
+        let board: Board = "###*\n###F".parse().unwrap();
+
+        assert_eq!(rate(&board, 0), Rating::OneGuess);
+    }
+
+    #[test]
+    fn guess_prefers_a_certainly_safe_tile() {
+        // The ones on the left decide the mine, which makes the tiles at (0, 2) and
+        // (1, 2) safe, though their estimate is higher than that of the far tiles.
+        let board: Board = "1*####\n11####".parse().unwrap();
+
+        let chances = mine_chances(&board);
+        assert!(chances
+            .iter()
+            .any(|&(idx, chance)| idx != 2 && chance < 0.2));
+        assert_eq!(suggest_guess(&board), Some((2, 0.0)));
+    }
+}
@@ -0,0 +1,63 @@
+use cli_minesweeper::{GameState, GameStatus};
+
+use crate::messages::{fill, Messages};
+
+/// Results of all games finished since the program was started.
+#[derive(Debug, Default)]
+pub struct SessionStats {
+    played: usize,
+    won_times: Vec<u64>,
+    /// Won games in which the `assist` command was used.
+    assisted_wins: usize,
+}
+
+impl SessionStats {
+    pub fn record(&mut self, state: &GameState) {
+        match state.status {
+            GameStatus::Playing => return,
+            GameStatus::Won => {
+                self.won_times.push(state.elapsed_seconds());
+                if state.assists_used > 0 {
+                    self.assisted_wins += 1;
+                }
+            }
+            GameStatus::Lost => {}
+        }
+
+        self.played += 1;
+    }
+
+    pub fn print(&self, messages: &Messages) {
+        if self.played == 0 {
+            return;
+        }
+
+        let won = self.won_times.len();
+        let win_rate = won as f64 / self.played as f64 * 100.0;
+        println!(
+            "\n{}",
+            fill(
+                messages.stats_games,
+                &[&self.played, &won, &format!("{:.0}", win_rate)]
+            )
+        );
+
+        if self.assisted_wins > 0 {
+            println!(
+                "{}",
+                fill(
+                    messages.stats_assisted,
+                    &[&self.assisted_wins, &(won - self.assisted_wins)]
+                )
+            );
+        }
+
+        if let Some(best) = self.won_times.iter().min() {
+            let average = self.won_times.iter().sum::<u64>() as f64 / won as f64;
+            println!(
+                "{}",
+                fill(messages.stats_times, &[best, &format!("{:.1}", average)])
+            );
+        }
+    }
+}
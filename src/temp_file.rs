@@ -0,0 +1,31 @@
+use std::{
+    env, fs,
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A file in the temp directory for a test that writes and reads back a file. Every
+/// instance gets its own path, so tests running in parallel do not collide, and the
+/// file is removed on drop.
+pub struct TempFile(PathBuf);
+
+impl TempFile {
+    pub fn new(extension: &str) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let name = format!("minesweeper-{}-{}.{}", process::id(), id, extension);
+        TempFile(env::temp_dir().join(name))
+    }
+
+    pub fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
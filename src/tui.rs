@@ -0,0 +1,113 @@
+use std::io::{self, Write};
+
+use cli_minesweeper::{Coord, Error, GameState, GameStatus};
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use rand::rngs::StdRng;
+
+use crate::{
+    config::Config, create_rng, flag_tile, open_tile, print_board, print_status, RenderOptions,
+};
+
+/// Plays the game full-screen: the board is redrawn in place and the highlighted tile is
+/// moved with the arrow keys.
+pub fn run(
+    mut state: GameState,
+    config: &Config,
+    options: &RenderOptions,
+    rng: &mut StdRng,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout();
+
+    terminal::enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, Hide)?;
+
+    let result = play(&mut state, config, options, rng);
+
+    execute!(stdout, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
+
+fn play(
+    state: &mut GameState,
+    config: &Config,
+    options: &RenderOptions,
+    rng: &mut StdRng,
+) -> Result<(), Error> {
+    let mut cursor = Coord::new(0, 0);
+    let mut message = None;
+    let messages = options.messages;
+
+    loop {
+        let row_count = state.board.row_count();
+        let col_count = state.board.col_count();
+        let tile_index = cursor.to_index(col_count);
+
+        let message_line = match state.status {
+            GameStatus::Playing => message
+                .take()
+                .unwrap_or_else(|| messages.tui_help.to_string()),
+            GameStatus::Won => messages.tui_won.to_string(),
+            GameStatus::Lost => messages.tui_lost.to_string(),
+        };
+        draw(state, options, tile_index, &message_line)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('r') => {
+                *state = state.restart();
+                *rng = create_rng(config.seed);
+            }
+            _ if state.status != GameStatus::Playing => {}
+            KeyCode::Up => cursor.row = cursor.row.saturating_sub(1),
+            KeyCode::Down => cursor.row = (cursor.row + 1).min(row_count - 1),
+            KeyCode::Left => cursor.col = cursor.col.saturating_sub(1),
+            KeyCode::Right => cursor.col = (cursor.col + 1).min(col_count - 1),
+            KeyCode::Char(' ') => message = open_tile(state, tile_index, config, rng, |_| {}),
+            KeyCode::Char('f') => message = flag_tile(state, tile_index, config),
+            KeyCode::Char('c') => state.chord(tile_index),
+            KeyCode::Char('u') => {
+                message = (!state.undo()).then(|| messages.nothing_to_undo.to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn draw(
+    state: &GameState,
+    options: &RenderOptions,
+    cursor: usize,
+    message: &str,
+) -> Result<(), Error> {
+    let highlight = (state.status == GameStatus::Playing).then_some(cursor);
+
+    let mut screen = Vec::new();
+    print_board(&mut screen, &state.board, options, highlight)?;
+    print_status(&mut screen, state, options)?;
+    writeln!(screen, "\n{}", message)?;
+
+    // Raw mode does not return the cursor to the start of the line on a newline.
+    let screen = String::from_utf8_lossy(&screen).replace('\n', "\r\n");
+
+    let mut stdout = io::stdout();
+    execute!(stdout, MoveTo(0, 0), Clear(ClearType::All))?;
+    write!(stdout, "{}", screen)?;
+    stdout.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use rand::{rngs::StdRng, SeedableRng};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{Coord, GameState, GameStatus, Tile};
+
+/// A game for web front-ends. The mines are placed from the given seed on the first
+/// open, keeping the first click and its neighbours free.
+#[wasm_bindgen]
+pub struct Game {
+    state: GameState,
+    rng: StdRng,
+}
+
+#[wasm_bindgen]
+impl Game {
+    #[wasm_bindgen(constructor)]
+    pub fn new(row_count: usize, col_count: usize, mine_count: usize, seed: u64) -> Game {
+        Game {
+            state: GameState::new(row_count, col_count, mine_count),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn open(&mut self, row: usize, col: usize) {
+        let Some(tile_idx) = self.tile_index(row, col) else {
+            return;
+        };
+
+        if !self.state.board.has_mines() {
+            let mine_count = self.state.mine_count;
+            self.state
+                .board
+                .generate_mines(mine_count, tile_idx, 1, &mut self.rng);
+        }
+
+        self.state.open(tile_idx);
+    }
+
+    pub fn flag(&mut self, row: usize, col: usize) {
+        if let Some(tile_idx) = self.tile_index(row, col) {
+            self.state.flag(tile_idx, false);
+        }
+    }
+
+    /// One of `playing`, `won` or `lost`.
+    pub fn status(&self) -> String {
+        match self.state.status {
+            GameStatus::Playing => "playing",
+            GameStatus::Won => "won",
+            GameStatus::Lost => "lost",
+        }
+        .to_string()
+    }
+
+    /// The board as the player sees it, one line per row.
+    pub fn render_to_string(&self) -> String {
+        let board = &self.state.board;
+
+        board
+            .tiles()
+            .chunks(board.col_count())
+            .map(|row| row.iter().map(tile_symbol).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn tile_index(&self, row: usize, col: usize) -> Option<usize> {
+        let board = &self.state.board;
+
+        (row < board.row_count() && col < board.col_count())
+            .then(|| Coord::new(row, col).to_index(board.col_count()))
+    }
+}
+
+fn tile_symbol(tile: &Tile) -> char {
+    match tile {
+        Tile::Flagged => 'F',
+        Tile::Question => '?',
+        Tile::Concealed => '#',
+        Tile::Open(0) => '.',
+        Tile::Open(count) => char::from_digit(*count as u32, 10).unwrap_or('?'),
+        Tile::Mine => '*',
+        Tile::TriggeredMine => '@',
+        Tile::WrongFlag => 'X',
+    }
+}